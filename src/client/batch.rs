@@ -0,0 +1,230 @@
+use crate::{
+    RpcClient, RpcMethod, RpcRequest, RpcResponse, SolProbeError, SolProbeResult, Transport,
+};
+use core::marker::PhantomData;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Requests sent together as one JSON array, created by `RpcClient::batch`
+pub struct RpcBatch<'a, T: Transport> {
+    client: &'a RpcClient<T>,
+    methods: Vec<RpcRequest>,
+    requests: Vec<serde_json::Value>,
+}
+
+/// Refers to one request of a batch, pass it to `BatchResponses::take` for its result
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BatchItem<R> {
+    id: u64,
+    result: PhantomData<fn() -> R>,
+}
+
+impl<R> BatchItem<R> {
+    /// The JSON-RPC id of the request
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl<'a, T: Transport> RpcBatch<'a, T> {
+    pub(crate) fn new(client: &'a RpcClient<T>) -> Self {
+        Self {
+            client,
+            methods: Vec::default(),
+            requests: Vec::default(),
+        }
+    }
+
+    /// Adds a call of `request` with `params`, which must serialize to a JSON array
+    pub fn add<P: Serialize, R: DeserializeOwned>(
+        &mut self,
+        request: RpcRequest,
+        params: P,
+    ) -> SolProbeResult<BatchItem<R>> {
+        let params = serde_json::to_value(params).map_err(|_| SolProbeError::InvalidRequest)?;
+        let id = self.client.next_id();
+
+        self.methods.push(request);
+        self.requests.push(request.build_request_json(id, params));
+
+        Ok(BatchItem {
+            id,
+            result: PhantomData,
+        })
+    }
+
+    /// Adds a call of the method `M`, for example `batch.call::<GetBlockTime>((slot,))`
+    pub fn call<M: RpcMethod>(
+        &mut self,
+        params: M::Params,
+    ) -> SolProbeResult<BatchItem<M::Result>> {
+        self.add(M::REQUEST, params)
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Sends every request in one round trip, retried and rate limited like a single request.
+    /// Fails as a whole only when the batch could not be delivered or the node rejected it,
+    /// errors of single requests are left in their results.
+    pub async fn send(self) -> SolProbeResult<BatchResponses> {
+        if self.requests.is_empty() {
+            return Ok(BatchResponses::default());
+        }
+
+        for request in &self.methods {
+            self.client.check_method(*request)?;
+        }
+
+        let body = serde_json::Value::Array(self.requests).to_string();
+
+        self.client
+            .execute(&self.methods, body, BatchResponses::parse)
+            .await
+    }
+}
+
+/// The responses to a batch, keyed by request id since nodes may answer out of order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchResponses {
+    responses: HashMap<u64, RpcResponse<serde_json::Value>>,
+}
+
+impl BatchResponses {
+    fn parse(response: &str) -> SolProbeResult<Self> {
+        let value: serde_json::Value = serde_json::from_str(response)
+            .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))?;
+
+        let responses: Vec<RpcResponse<serde_json::Value>> = match value {
+            serde_json::Value::Array(_) => serde_json::from_value(value)
+                .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))?,
+            // A single error object answers a batch the node could not read at all
+            value => {
+                serde_json::from_value::<RpcResponse<serde_json::Value>>(value)
+                    .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))?
+                    .into_result()?;
+
+                return Err(SolProbeError::InvalidResponse(
+                    "a batch must be answered with an array".to_string(),
+                ));
+            }
+        };
+
+        let responses = responses
+            .into_iter()
+            .filter_map(|response| response.id.map(|id| (id, response)))
+            .collect();
+
+        Ok(Self { responses })
+    }
+
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// The result of the request `item`, `SolProbeError::InvalidResponse` when the node
+    /// left it unanswered
+    pub fn take<R: DeserializeOwned>(&mut self, item: &BatchItem<R>) -> SolProbeResult<R> {
+        let result = self
+            .responses
+            .remove(&item.id)
+            .ok_or_else(|| {
+                SolProbeError::InvalidResponse(format!("no response to request {}", item.id))
+            })?
+            .into_result()?;
+
+        serde_json::from_value(result)
+            .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))
+    }
+}
+
+#[test]
+fn batch_matches_responses_by_id() {
+    use crate::{
+        Fixture, GetBlockTime, MockRpcServer, RetryPolicy, RpcErrorKind, SleepFuture, Timer,
+    };
+    use core::time::Duration;
+    use serde_json::json;
+
+    struct Immediately;
+
+    impl Timer for Immediately {
+        fn sleep(&self, _duration: Duration) -> SleepFuture<'_> {
+            Box::pin(async {})
+        }
+
+        fn now(&self) -> Duration {
+            Duration::default()
+        }
+    }
+
+    let mut server = MockRpcServer::new();
+    server
+        .result("getBlockTime", json!([100]), json!(null))
+        .insert(Fixture {
+            method: "getBlockTime".to_string(),
+            params: json!([101]),
+            response: json!({
+                "jsonrpc": "2.0",
+                "error": { "code": -32007, "message": "Slot 101 was skipped" },
+                "id": 0
+            }),
+        })
+        .result("getBlockTime", json!([102]), json!(1_700_000_100))
+        .result("getBlockTime", json!([103]), json!(1_700_000_200));
+    // Throttles the first attempt of the batch
+    server.fail(SolProbeError::Http {
+        status: 429,
+        retry_after: None,
+    });
+
+    let mut client = RpcClient::new(crate::SOLANA_DEVNET_URL, server);
+    client.retry(RetryPolicy::new()).timer(Immediately);
+    let mut batch = client.batch();
+    let items = (100..104)
+        .map(|slot| batch.call::<GetBlockTime>((slot,)).unwrap())
+        .collect::<Vec<_>>();
+    let mut responses = smol::block_on(batch.send()).unwrap();
+
+    // The throttled batch is sent again as is
+    let requests = client.transport().requests();
+    assert_eq!(requests.len(), 8);
+    assert_eq!(requests[..4], requests[4..]);
+    assert_eq!(requests[7]["params"], json!([103]));
+    assert_eq!(responses.take(&items[0]), Ok(None));
+    match responses.take(&items[1]) {
+        Err(SolProbeError::Rpc(error)) => assert_eq!(error.kind(), RpcErrorKind::SlotSkipped),
+        other => panic!("expected an RPC error, got {:?}", other),
+    }
+    assert_eq!(responses.take(&items[2]), Ok(Some(1_700_000_100)));
+    assert_eq!(responses.take(&items[3]), Ok(Some(1_700_000_200)));
+
+    // Nodes may answer out of order or leave requests unanswered
+    let mut responses = BatchResponses::parse(&format!(
+        r#"[
+            {{ "jsonrpc": "2.0", "id": {}, "result": 1700000100 }},
+            {{ "jsonrpc": "2.0", "id": {}, "result": null }}
+        ]"#,
+        items[2].id, items[0].id
+    ))
+    .unwrap();
+    assert_eq!(responses.take(&items[0]), Ok(None));
+    assert_eq!(responses.take(&items[2]), Ok(Some(1_700_000_100)));
+    assert_eq!(
+        responses.take(&items[3]),
+        Err(SolProbeError::InvalidResponse(format!(
+            "no response to request {}",
+            items[3].id
+        )))
+    );
+}
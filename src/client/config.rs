@@ -0,0 +1,463 @@
+use crate::{Pubkey, Signature, Slot, UiAccountEncoding, UiTransactionEncoding};
+use serde::{Deserialize, Serialize};
+
+/// How far the cluster must have confirmed the state a request reads
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommitmentLevel {
+    /// The node's most recent block, which may still be skipped by the cluster
+    Processed,
+    /// Voted on by a supermajority of the cluster
+    Confirmed,
+    /// Rooted by a supermajority of the cluster, the default of most methods
+    Finalized,
+}
+
+/// How much of each transaction `getBlock` returns
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionDetails {
+    /// Transactions with their message and metadata
+    Full,
+    /// Only the signature of each transaction, in `EncodedConfirmedBlock::signatures`
+    Signatures,
+    /// The signatures, account list and balances of each transaction without instructions
+    Accounts,
+    /// No transactions at all
+    None,
+}
+
+/// The configuration object of `getBlock`. Unset fields are left out of the request so the
+/// node applies its own defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<UiTransactionEncoding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_details: Option<TransactionDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewards: Option<bool>,
+    /// `Processed` is not supported by `getBlock`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    /// Nodes reject blocks holding versioned transactions unless this is set, `0` being
+    /// the only version so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_supported_transaction_version: Option<u8>,
+}
+
+impl GetBlockConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn encoding(&mut self, encoding: UiTransactionEncoding) -> &mut Self {
+        self.encoding = Some(encoding);
+
+        self
+    }
+
+    pub fn transaction_details(&mut self, transaction_details: TransactionDetails) -> &mut Self {
+        self.transaction_details = Some(transaction_details);
+
+        self
+    }
+
+    pub fn rewards(&mut self, rewards: bool) -> &mut Self {
+        self.rewards = Some(rewards);
+
+        self
+    }
+
+    pub fn commitment(&mut self, commitment: CommitmentLevel) -> &mut Self {
+        self.commitment = Some(commitment);
+
+        self
+    }
+
+    pub fn max_supported_transaction_version(&mut self, version: u8) -> &mut Self {
+        self.max_supported_transaction_version = Some(version);
+
+        self
+    }
+
+    /// The params array of `RpcRequest::GetBlock` for the block at `slot`
+    pub fn to_params(&self, slot: Slot) -> serde_json::Value {
+        serde_json::json!([slot, self])
+    }
+}
+
+/// The blocks `blockSubscribe` notifies about
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockSubscribeFilter {
+    All,
+    /// Blocks with a transaction that reads or writes this account or calls this program
+    MentionsAccountOrProgram(Pubkey),
+}
+
+/// The configuration of `blockSubscribe`, which unlike `getBlock` names `rewards`
+/// `showRewards`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockSubscribeConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<UiTransactionEncoding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_details: Option<TransactionDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_rewards: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_supported_transaction_version: Option<u8>,
+}
+
+/// The transactions `logsSubscribe` notifies about
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogsFilter {
+    /// Every transaction but simple votes
+    All,
+    AllWithVotes,
+    /// Transactions mentioning the account, nodes accept a single one
+    Mentions(Vec<Pubkey>),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureSubscribeConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    /// Also notifies when the node receives the transaction, before it is processed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_received_notification: Option<bool>,
+}
+
+/// The commitment and minimum context slot accepted by most read methods
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    /// The node fails the request instead of answering from a slot older than this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<Slot>,
+}
+
+impl CommitmentConfig {
+    pub fn new(commitment: CommitmentLevel) -> Self {
+        Self {
+            commitment: Some(commitment),
+            min_context_slot: None,
+        }
+    }
+}
+
+/// The configuration of `getAccountInfo`, `getMultipleAccounts` and the token account queries
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfoConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<UiAccountEncoding>,
+    /// Returns only this range of the account data, for binary encodings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_slice: Option<DataSlice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<Slot>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataSlice {
+    pub offset: usize,
+    pub length: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramAccountsConfig {
+    /// Every filter must match for an account to be returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<Vec<ProgramAccountsFilter>>,
+    #[serde(flatten)]
+    pub account_config: AccountInfoConfig,
+    /// Wraps the result in `{context, value}`, see `OptionalContext`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with_context: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProgramAccountsFilter {
+    /// Accounts whose data is exactly this many bytes long
+    DataSize(u64),
+    /// Accounts whose data holds `bytes` at `offset`
+    Memcmp(Memcmp),
+    /// Valid token accounts, for the token programs only
+    TokenAccountState,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Memcmp {
+    pub offset: usize,
+    /// Base58 encoded bytes unless `encoding` says otherwise
+    pub bytes: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<MemcmpEncoding>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemcmpEncoding {
+    Base58,
+    Base64,
+}
+
+/// Selects the token accounts of `getTokenAccountsByOwner` and `getTokenAccountsByDelegate`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenAccountsFilter {
+    Mint(Pubkey),
+    ProgramId(Pubkey),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignaturesForAddressConfig {
+    /// At most 1000, the node's default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Starts searching backwards from this transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Signature>,
+    /// Stops searching at this transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<Signature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<Slot>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureStatusConfig {
+    /// Looks the signatures up in the ledger instead of only the recent status cache
+    pub search_transaction_history: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    pub exclude_non_circulating_accounts_list: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteAccountsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    /// Only returns this vote account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vote_pubkey: Option<Pubkey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_unstaked_delinquents: Option<bool>,
+    /// Slots behind the tip after which a validator counts as delinquent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delinquent_slot_distance: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LargestAccountsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<LargestAccountsFilter>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LargestAccountsFilter {
+    Circulating,
+    NonCirculating,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderScheduleConfig {
+    /// Only returns the slots of this validator identity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<Pubkey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+}
+
+/// The configuration of the methods that take an epoch, defaulting to the previous epoch
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<Slot>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockProductionConfig {
+    /// Only returns the production of this validator identity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<Pubkey>,
+    /// Defaults to the current epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<BlockProductionRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockProductionRange {
+    pub first_slot: Slot,
+    /// Defaults to the highest slot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_slot: Option<Slot>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<UiTransactionEncoding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    /// Nodes reject versioned transactions unless this is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_supported_transaction_version: Option<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTransactionConfig {
+    pub skip_preflight: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preflight_commitment: Option<CommitmentLevel>,
+    /// The encoding of the transaction, `Base58` when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<UiTransactionEncoding>,
+    /// How many times the node retries sending the transaction to the leader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<Slot>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateTransactionConfig {
+    pub sig_verify: bool,
+    /// Simulates with the latest blockhash, conflicts with `sig_verify`
+    pub replace_recent_blockhash: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<CommitmentLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Accounts to return in the state they would have after the transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<SimulateTransactionAccountsConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<Slot>,
+    pub inner_instructions: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateTransactionAccountsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<UiAccountEncoding>,
+    pub addresses: Vec<Pubkey>,
+}
+
+#[test]
+fn get_block_config_params_and_reduced_blocks() {
+    use crate::ConfirmedBlock;
+
+    let mut config = GetBlockConfig::new();
+    config
+        .transaction_details(TransactionDetails::Signatures)
+        .rewards(false)
+        .commitment(CommitmentLevel::Confirmed);
+    assert_eq!(
+        config.to_params(42),
+        serde_json::json!([42, {
+            "transactionDetails": "signatures",
+            "rewards": false,
+            "commitment": "confirmed"
+        }])
+    );
+
+    let signatures: crate::EncodedConfirmedBlock = serde_json::from_str(
+        r#"{
+            "previousBlockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+            "blockhash": "4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZAMdL4VZHirAn",
+            "parentSlot": 41,
+            "signatures": ["5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv"],
+            "blockTime": null,
+            "blockHeight": 40
+        }"#,
+    )
+    .unwrap();
+    let mut block = ConfirmedBlock::new();
+    block.decode(&signatures).unwrap();
+    assert!(block.transactions.is_empty());
+    assert_eq!(block.signatures.len(), 1);
+
+    let accounts: crate::EncodedConfirmedBlock = serde_json::from_str(
+        r#"{
+            "previousBlockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+            "blockhash": "4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZAMdL4VZHirAn",
+            "parentSlot": 41,
+            "transactions": [{
+                "transaction": {
+                    "signatures": ["5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv"],
+                    "accountKeys": [
+                        { "pubkey": "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe", "signer": true, "writable": true, "source": "transaction" },
+                        { "pubkey": "11111111111111111111111111111111", "signer": false, "writable": false, "source": "transaction" },
+                        { "pubkey": "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc", "signer": false, "writable": true, "source": "lookupTable" }
+                    ]
+                },
+                "meta": {
+                    "err": null,
+                    "status": { "Ok": null },
+                    "fee": 5000,
+                    "preBalances": [10000, 1, 0],
+                    "postBalances": [4000, 1, 1000]
+                },
+                "version": 0
+            }],
+            "blockTime": 1700000000,
+            "blockHeight": 40
+        }"#,
+    )
+    .unwrap();
+    let mut block = ConfirmedBlock::new();
+    block.decode(&accounts).unwrap();
+    let transaction = &block.transactions[0];
+    assert!(transaction.message.instructions.is_empty());
+    assert_eq!(transaction.message.loaded_addresses.writable.len(), 1);
+    assert_eq!(
+        transaction.balance_changes().unwrap()[2].change.lamports(),
+        1000
+    );
+    assert_eq!(block.signatures, transaction.signatures);
+}
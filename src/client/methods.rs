@@ -0,0 +1,282 @@
+use crate::{
+    AccountInfoConfig, BlockProductionConfig, CommitmentConfig, EncodedConfirmedBlock,
+    EncodedConfirmedTransaction, EpochConfig, EpochInfo, EpochSchedule, GetBlockConfig, Lamports,
+    LargestAccountsConfig, LeaderScheduleConfig, OptionalContext, ProgramAccountsConfig, Pubkey,
+    RpcAccountBalance, RpcBlockCommitment, RpcBlockProduction, RpcBlockhash,
+    RpcBlockhashFeeCalculator, RpcConfirmedTransactionStatusWithSignature, RpcContactInfo,
+    RpcFeeCalculator, RpcFeeRateGovernor, RpcFees, RpcIdentity, RpcInflationGovernor,
+    RpcInflationRate, RpcInflationReward, RpcKeyedAccount, RpcLeaderSchedule, RpcPerfSample,
+    RpcPrioritizationFee, RpcRequest, RpcSimulateTransactionResult, RpcSnapshotSlotInfo,
+    RpcStakeActivation, RpcSupply, RpcTokenAccountBalance, RpcVersionInfo, RpcVoteAccountStatus,
+    SendTransactionConfig, Signature, SignatureStatusConfig, SignaturesForAddressConfig,
+    SimulateTransactionConfig, Slot, SupplyConfig, TokenAccountsFilter, TokenQuantity,
+    TransactionConfig, TransactionStatus, UiAccount, UnixTimestamp, VoteAccountsConfig,
+    WithContext,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Ties an `RpcRequest` to the types of its params and result so that
+/// `RpcClient::call::<GetBalance>((pubkey, None))` is checked at compile time
+pub trait RpcMethod {
+    const REQUEST: RpcRequest;
+
+    /// The positional params, serialized as a JSON array. Trailing `Option` configs are
+    /// sent as `null` when `None`, which the node reads as its defaults.
+    type Params: Serialize;
+
+    type Result: DeserializeOwned;
+}
+
+/// Declares a unit struct named after the `RpcRequest` variant it implements `RpcMethod` for
+macro_rules! rpc_method {
+    ($(#[$attr:meta])* $name:ident, $params:ty, $result:ty) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        #[allow(deprecated)]
+        impl RpcMethod for $name {
+            const REQUEST: RpcRequest = RpcRequest::$name;
+            type Params = $params;
+            type Result = $result;
+        }
+    };
+}
+
+rpc_method!(
+    GetAccountInfo,
+    (Pubkey, Option<AccountInfoConfig>),
+    WithContext<Option<UiAccount>>
+);
+rpc_method!(
+    GetBalance,
+    (Pubkey, Option<CommitmentConfig>),
+    WithContext<Lamports>
+);
+rpc_method!(
+    GetBlock,
+    (Slot, Option<GetBlockConfig>),
+    EncodedConfirmedBlock
+);
+rpc_method!(GetBlockCommitment, (Slot,), RpcBlockCommitment);
+rpc_method!(GetBlockHeight, (Option<CommitmentConfig>,), u64);
+rpc_method!(
+    GetBlockProduction,
+    (Option<BlockProductionConfig>,),
+    WithContext<RpcBlockProduction>
+);
+rpc_method!(
+    GetBlocks,
+    (Slot, Option<Slot>, Option<CommitmentConfig>),
+    Vec<Slot>
+);
+rpc_method!(
+    GetBlocksWithLimit,
+    (Slot, u64, Option<CommitmentConfig>),
+    Vec<Slot>
+);
+rpc_method!(GetBlockTime, (Slot,), Option<UnixTimestamp>);
+rpc_method!(GetClusterNodes, (), Vec<RpcContactInfo>);
+rpc_method!(GetEpochInfo, (Option<CommitmentConfig>,), EpochInfo);
+rpc_method!(GetEpochSchedule, (), EpochSchedule);
+rpc_method!(
+    #[deprecated(note = "removed in Solana 2.0, use `IsBlockhashValid` or `GetFeeForMessage`")]
+    GetFeeCalculatorForBlockhash,
+    (crate::Hash, Option<CommitmentConfig>),
+    WithContext<Option<RpcFeeCalculator>>
+);
+rpc_method!(
+    GetFeeForMessage,
+    (String, Option<CommitmentConfig>),
+    WithContext<Option<Lamports>>
+);
+rpc_method!(
+    #[deprecated(note = "removed in Solana 2.0, use `GetFeeForMessage`")]
+    GetFeeRateGovernor,
+    (),
+    WithContext<RpcFeeRateGovernor>
+);
+rpc_method!(
+    #[deprecated(note = "removed in Solana 2.0, use `GetFeeForMessage`")]
+    GetFees,
+    (Option<CommitmentConfig>,),
+    WithContext<RpcFees>
+);
+rpc_method!(GetFirstAvailableBlock, (), Slot);
+rpc_method!(GetGenesisHash, (), crate::Hash);
+rpc_method!(GetHealth, (), String);
+rpc_method!(GetHighestSnapshotSlot, (), RpcSnapshotSlotInfo);
+rpc_method!(GetIdentity, (), RpcIdentity);
+rpc_method!(
+    GetInflationGovernor,
+    (Option<CommitmentConfig>,),
+    RpcInflationGovernor
+);
+rpc_method!(GetInflationRate, (), RpcInflationRate);
+rpc_method!(
+    GetInflationReward,
+    (Vec<Pubkey>, Option<EpochConfig>),
+    Vec<Option<RpcInflationReward>>
+);
+rpc_method!(
+    GetLargestAccounts,
+    (Option<LargestAccountsConfig>,),
+    WithContext<Vec<RpcAccountBalance>>
+);
+rpc_method!(
+    GetLatestBlockhash,
+    (Option<CommitmentConfig>,),
+    WithContext<RpcBlockhash>
+);
+rpc_method!(
+    GetLeaderSchedule,
+    (Option<Slot>, Option<LeaderScheduleConfig>),
+    Option<RpcLeaderSchedule>
+);
+rpc_method!(GetMaxRetransmitSlot, (), Slot);
+rpc_method!(GetMaxShredInsertSlot, (), Slot);
+rpc_method!(
+    GetMinimumBalanceForRentExemption,
+    (u64, Option<CommitmentConfig>),
+    Lamports
+);
+rpc_method!(
+    GetMultipleAccounts,
+    (Vec<Pubkey>, Option<AccountInfoConfig>),
+    WithContext<Vec<Option<UiAccount>>>
+);
+rpc_method!(
+    GetProgramAccounts,
+    (Pubkey, Option<ProgramAccountsConfig>),
+    OptionalContext<Vec<RpcKeyedAccount>>
+);
+rpc_method!(
+    #[deprecated(note = "removed in Solana 2.0, use `GetLatestBlockhash`")]
+    GetRecentBlockhash,
+    (Option<CommitmentConfig>,),
+    WithContext<RpcBlockhashFeeCalculator>
+);
+rpc_method!(
+    GetRecentPerformanceSamples,
+    (Option<u64>,),
+    Vec<RpcPerfSample>
+);
+rpc_method!(
+    GetRecentPrioritizationFees,
+    (Option<Vec<Pubkey>>,),
+    Vec<RpcPrioritizationFee>
+);
+rpc_method!(
+    #[deprecated(note = "removed in Solana 2.0, use `GetHighestSnapshotSlot`")]
+    GetSnapshotSlot,
+    (),
+    Slot
+);
+rpc_method!(
+    GetSignaturesForAddress,
+    (Pubkey, Option<SignaturesForAddressConfig>),
+    Vec<RpcConfirmedTransactionStatusWithSignature>
+);
+rpc_method!(
+    GetSignatureStatuses,
+    (Vec<Signature>, Option<SignatureStatusConfig>),
+    WithContext<Vec<Option<TransactionStatus>>>
+);
+rpc_method!(GetSlot, (Option<CommitmentConfig>,), Slot);
+rpc_method!(GetSlotLeader, (Option<CommitmentConfig>,), Pubkey);
+rpc_method!(GetSlotLeaders, (Slot, u64), Vec<Pubkey>);
+rpc_method!(
+    #[deprecated(note = "removed in Solana 2.0")]
+    GetStakeActivation,
+    (Pubkey, Option<EpochConfig>),
+    RpcStakeActivation
+);
+rpc_method!(
+    GetStakeMinimumDelegation,
+    (Option<CommitmentConfig>,),
+    WithContext<Lamports>
+);
+rpc_method!(GetSupply, (Option<SupplyConfig>,), WithContext<RpcSupply>);
+rpc_method!(
+    GetTokenAccountBalance,
+    (Pubkey, Option<CommitmentConfig>),
+    WithContext<TokenQuantity>
+);
+rpc_method!(
+    GetTokenAccountsByDelegate,
+    (Pubkey, TokenAccountsFilter, Option<AccountInfoConfig>),
+    WithContext<Vec<RpcKeyedAccount>>
+);
+rpc_method!(
+    GetTokenAccountsByOwner,
+    (Pubkey, TokenAccountsFilter, Option<AccountInfoConfig>),
+    WithContext<Vec<RpcKeyedAccount>>
+);
+rpc_method!(
+    GetTokenLargestAccounts,
+    (Pubkey, Option<CommitmentConfig>),
+    WithContext<Vec<RpcTokenAccountBalance>>
+);
+rpc_method!(
+    GetTokenSupply,
+    (Pubkey, Option<CommitmentConfig>),
+    WithContext<TokenQuantity>
+);
+rpc_method!(
+    GetTransaction,
+    (Signature, Option<TransactionConfig>),
+    Option<EncodedConfirmedTransaction>
+);
+rpc_method!(GetTransactionCount, (Option<CommitmentConfig>,), u64);
+rpc_method!(GetVersion, (), RpcVersionInfo);
+rpc_method!(
+    GetVoteAccounts,
+    (Option<VoteAccountsConfig>,),
+    RpcVoteAccountStatus
+);
+rpc_method!(
+    IsBlockhashValid,
+    (crate::Hash, Option<CommitmentConfig>),
+    WithContext<bool>
+);
+rpc_method!(MinimumLedgerSlot, (), Slot);
+rpc_method!(
+    RequestAirdrop,
+    (Pubkey, Lamports, Option<CommitmentConfig>),
+    Signature
+);
+rpc_method!(
+    SendTransaction,
+    (String, Option<SendTransactionConfig>),
+    Signature
+);
+rpc_method!(
+    SimulateTransaction,
+    (String, Option<SimulateTransactionConfig>),
+    WithContext<RpcSimulateTransactionResult>
+);
+
+#[test]
+fn call_checks_params_and_result_types() {
+    use crate::{MockRpcServer, RpcClient};
+    use serde_json::json;
+
+    let mut server = MockRpcServer::new();
+    server.result(
+        "getBalance",
+        json!(["11111111111111111111111111111111", null]),
+        json!({ "context": { "slot": 7 }, "value": 5000 }),
+    );
+    let client = RpcClient::new(crate::SOLANA_DEVNET_URL, server);
+    let balance = smol::block_on(client.call::<GetBalance>((Pubkey::default(), None))).unwrap();
+
+    assert_eq!(balance.value, Lamports(5000));
+    assert_eq!(balance.context.slot, 7);
+    let requests = client.transport().requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0]["params"],
+        json!(["11111111111111111111111111111111", null])
+    );
+}
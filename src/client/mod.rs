@@ -0,0 +1,20 @@
+mod transport;
+pub use transport::*;
+mod config;
+pub use config::*;
+mod models;
+pub use models::*;
+mod methods;
+pub use methods::*;
+mod response;
+pub use response::*;
+mod batch;
+pub use batch::*;
+mod pubsub;
+pub use pubsub::*;
+mod pool;
+pub use pool::*;
+mod retry;
+pub use retry::*;
+mod rpc_client;
+pub use rpc_client::*;
@@ -0,0 +1,548 @@
+use crate::{
+    ConfirmedBlock, Lamports, Pubkey, Signature, Slot, SolProbeResult, TokenQuantity,
+    TransactionError, UnixTimestamp,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// The slot the node answered from, returned alongside the value of most account and bank queries
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcResponseContext {
+    pub slot: Slot,
+    /// The version of the node's RPC API, absent on nodes older than v1.15
+    pub api_version: Option<String>,
+}
+
+/// The `{context, value}` wrapper of methods that report the slot they read from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithContext<T> {
+    pub context: RpcResponseContext,
+    pub value: T,
+}
+
+/// A result that is only wrapped in `{context, value}` when the request asked for it,
+/// like `getProgramAccounts` with `withContext`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OptionalContext<T> {
+    Context(WithContext<T>),
+    NoContext(T),
+}
+
+impl<T> OptionalContext<T> {
+    pub fn into_value(self) -> T {
+        match self {
+            OptionalContext::Context(with_context) => with_context.value,
+            OptionalContext::NoContext(value) => value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UiAccountEncoding {
+    Binary, // Legacy. Retained for RPC backwards compatibility
+    Base58,
+    Base64,
+    JsonParsed,
+    #[serde(rename = "base64+zstd")]
+    Base64Zstd,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAccount {
+    pub lamports: Lamports,
+    pub data: UiAccountData,
+    /// The program that owns the account
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    /// The length of the account data in bytes, absent on older nodes
+    pub space: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", untagged)]
+pub enum UiAccountData {
+    LegacyBinary(String), // Old way of expressing base-58, retained for RPC backwards compatibility
+    Json(ParsedAccountData),
+    Binary(String, UiAccountEncoding),
+}
+
+/// Account data the node decoded for a `jsonParsed` request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedAccountData {
+    pub program: String,
+    pub parsed: serde_json::Value,
+    pub space: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcKeyedAccount {
+    pub pubkey: Pubkey,
+    pub account: UiAccount,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountBalance {
+    pub address: Pubkey,
+    pub lamports: Lamports,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenAccountBalance {
+    pub address: Pubkey,
+    #[serde(flatten)]
+    pub amount: TokenQuantity,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockCommitment {
+    /// Stake voted at each lockout depth, `None` when the node does not know the block
+    pub commitment: Option<Vec<u64>>,
+    pub total_stake: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockProduction {
+    /// Leader slots and blocks produced per validator identity
+    pub by_identity: HashMap<Pubkey, (u64, u64)>,
+    pub range: RpcBlockProductionRange,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockProductionRange {
+    pub first_slot: Slot,
+    pub last_slot: Slot,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContactInfo {
+    /// Identity of the node
+    pub pubkey: Pubkey,
+    pub gossip: Option<String>,
+    pub tpu: Option<String>,
+    pub tpu_quic: Option<String>,
+    pub rpc: Option<String>,
+    pub pubsub: Option<String>,
+    pub version: Option<String>,
+    pub feature_set: Option<u32>,
+    pub shred_version: Option<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochInfo {
+    pub epoch: u64,
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+    pub absolute_slot: Slot,
+    pub block_height: u64,
+    pub transaction_count: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochSchedule {
+    pub slots_per_epoch: u64,
+    pub leader_schedule_slot_offset: u64,
+    /// Whether the first epochs are shorter and grow to `slots_per_epoch`
+    pub warmup: bool,
+    pub first_normal_epoch: u64,
+    pub first_normal_slot: Slot,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockhash {
+    pub blockhash: crate::Hash,
+    /// The last block height at which a transaction using `blockhash` is accepted
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPrioritizationFee {
+    pub slot: Slot,
+    /// The minimum fee paid by a transaction of the slot, in micro-lamports per compute unit
+    pub prioritization_fee: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSnapshotSlotInfo {
+    pub full: Slot,
+    pub incremental: Option<Slot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeCalculator {
+    pub lamports_per_signature: Lamports,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeeCalculator {
+    pub fee_calculator: FeeCalculator,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRateGovernor {
+    pub lamports_per_signature: Lamports,
+    pub target_lamports_per_signature: Lamports,
+    pub target_signatures_per_slot: u64,
+    pub min_lamports_per_signature: Lamports,
+    pub max_lamports_per_signature: Lamports,
+    /// Percentage of the fees that is burned
+    pub burn_percent: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFeeRateGovernor {
+    pub fee_rate_governor: FeeRateGovernor,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFees {
+    pub blockhash: crate::Hash,
+    pub fee_calculator: FeeCalculator,
+    pub last_valid_slot: Slot,
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockhashFeeCalculator {
+    pub blockhash: crate::Hash,
+    pub fee_calculator: FeeCalculator,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcIdentity {
+    pub identity: Pubkey,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInflationGovernor {
+    pub initial: f64,
+    pub terminal: f64,
+    /// Rate per year at which inflation is lowered
+    pub taper: f64,
+    pub foundation: f64,
+    /// Duration of the foundation pool inflation in years
+    pub foundation_term: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInflationRate {
+    pub total: f64,
+    pub validator: f64,
+    pub foundation: f64,
+    pub epoch: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInflationReward {
+    pub epoch: u64,
+    pub effective_slot: Slot,
+    pub amount: Lamports,
+    pub post_balance: Lamports,
+    /// Vote account commission when the reward was credited
+    pub commission: Option<u8>,
+}
+
+/// Leader slots per validator identity, as offsets from the first slot of the epoch
+pub type RpcLeaderSchedule = BTreeMap<Pubkey, Vec<u64>>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPerfSample {
+    pub slot: Slot,
+    pub num_transactions: u64,
+    /// Absent on nodes older than v1.15
+    pub num_non_vote_transactions: Option<u64>,
+    pub num_slots: u64,
+    pub sample_period_secs: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionConfirmationStatus {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConfirmedTransactionStatusWithSignature {
+    pub signature: Signature,
+    pub slot: Slot,
+    pub err: Option<TransactionError>,
+    pub memo: Option<String>,
+    pub block_time: Option<UnixTimestamp>,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatus {
+    pub slot: Slot,
+    /// Blocks confirmed since the transaction's block, `None` once it is rooted
+    pub confirmations: Option<u64>,
+    pub err: Option<TransactionError>,
+    pub confirmation_status: Option<TransactionConfirmationStatus>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StakeActivationState {
+    Activating,
+    Active,
+    Deactivating,
+    Inactive,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcStakeActivation {
+    pub state: StakeActivationState,
+    pub active: Lamports,
+    pub inactive: Lamports,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSupply {
+    pub total: Lamports,
+    pub circulating: Lamports,
+    pub non_circulating: Lamports,
+    /// Empty when requested with `exclude_non_circulating_accounts_list`
+    pub non_circulating_accounts: Vec<Pubkey>,
+}
+
+/// The notification of `slotSubscribe`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotInfo {
+    pub slot: Slot,
+    pub parent: Slot,
+    /// The latest rooted slot
+    pub root: Slot,
+}
+
+/// The notification of `blockSubscribe`, pass it to `RpcBlockUpdate::decode` for the
+/// simplified block
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlockUpdate {
+    pub slot: Slot,
+    /// `None` when the node failed to load the block, see `err`
+    pub block: Option<crate::EncodedConfirmedBlock>,
+    pub err: Option<serde_json::Value>,
+}
+
+impl RpcBlockUpdate {
+    pub fn decode(&self) -> SolProbeResult<Option<ConfirmedBlock>> {
+        match &self.block {
+            Some(block) => {
+                let mut confirmed_block = ConfirmedBlock::new();
+                confirmed_block.decode(block)?;
+
+                Ok(Some(confirmed_block))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// The notification of `logsSubscribe`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLogsResponse {
+    pub signature: Signature,
+    pub err: Option<TransactionError>,
+    /// Pass them to `parse_logs` for the invocation tree
+    pub logs: Vec<String>,
+}
+
+/// The notification of `signatureSubscribe`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RpcSignatureResult {
+    /// The transaction reached the subscribed commitment, the subscription then ends
+    Processed { err: Option<TransactionError> },
+    /// The node received the transaction, with `enable_received_notification` only
+    Received(ReceivedSignature),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReceivedSignature {
+    ReceivedSignature,
+}
+
+/// A transaction returned by `getTransaction`, pass `transaction` to `Transaction::decode_tx`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedConfirmedTransaction {
+    pub slot: Slot,
+    #[serde(flatten)]
+    pub transaction: crate::EncodedTransactionWithStatusMeta,
+    pub block_time: Option<UnixTimestamp>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcVersionInfo {
+    #[serde(rename = "solana-core")]
+    pub solana_core: String,
+    #[serde(rename = "feature-set")]
+    pub feature_set: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVoteAccountStatus {
+    pub current: Vec<RpcVoteAccountInfo>,
+    pub delinquent: Vec<RpcVoteAccountInfo>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcVoteAccountInfo {
+    pub vote_pubkey: Pubkey,
+    /// Identity of the validator
+    pub node_pubkey: Pubkey,
+    pub activated_stake: Lamports,
+    pub commission: u8,
+    /// Whether the account is staked for the current epoch
+    pub epoch_vote_account: bool,
+    /// `(epoch, credits, previous_credits)` for the latest epochs
+    pub epoch_credits: Vec<(u64, u64, u64)>,
+    pub last_vote: Slot,
+    pub root_slot: Slot,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateTransactionResult {
+    pub err: Option<TransactionError>,
+    pub logs: Option<Vec<String>>,
+    /// The requested accounts in the state they would have after the transaction
+    pub accounts: Option<Vec<Option<UiAccount>>>,
+    pub units_consumed: Option<u64>,
+    pub return_data: Option<UiTransactionReturnData>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTransactionReturnData {
+    pub program_id: Pubkey,
+    /// The returned bytes, base64 encoded
+    pub data: (String, UiReturnDataEncoding),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UiReturnDataEncoding {
+    Base64,
+}
+
+#[test]
+fn deserialize_rpc_models() {
+    let balance: WithContext<Lamports> = serde_json::from_str(
+        r#"{ "context": { "apiVersion": "1.18.22", "slot": 310000000 }, "value": 5000 }"#,
+    )
+    .unwrap();
+    assert_eq!(balance.value, Lamports(5000));
+    assert_eq!(balance.context.api_version.as_deref(), Some("1.18.22"));
+
+    let account: WithContext<Option<UiAccount>> = serde_json::from_str(
+        r#"{
+            "context": { "slot": 1 },
+            "value": {
+                "data": ["AQID", "base64"],
+                "executable": false,
+                "lamports": 1461600,
+                "owner": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                "rentEpoch": 18446744073709551615,
+                "space": 82
+            }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        account.value.unwrap().data,
+        UiAccountData::Binary("AQID".to_string(), UiAccountEncoding::Base64)
+    );
+
+    let statuses: WithContext<Vec<Option<TransactionStatus>>> = serde_json::from_str(
+        r#"{
+            "context": { "slot": 82 },
+            "value": [
+                { "slot": 72, "confirmations": 10, "err": null, "status": { "Ok": null }, "confirmationStatus": "confirmed" },
+                null
+            ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        statuses.value[0].as_ref().unwrap().confirmation_status,
+        Some(TransactionConfirmationStatus::Confirmed)
+    );
+    assert_eq!(statuses.value[1], None);
+
+    let version: RpcVersionInfo =
+        serde_json::from_str(r#"{ "solana-core": "1.18.22", "feature-set": 3241752014 }"#).unwrap();
+    assert_eq!(version.solana_core, "1.18.22");
+
+    let transaction: EncodedConfirmedTransaction = serde_json::from_str(
+        r#"{
+            "slot": 430,
+            "blockTime": null,
+            "transaction": ["AQID", "base64"],
+            "meta": null,
+            "version": 0
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(transaction.slot, 430);
+    assert_eq!(
+        transaction.transaction.version,
+        Some(crate::TransactionVersion::Number(0))
+    );
+
+    let largest: Vec<RpcTokenAccountBalance> = serde_json::from_str(
+        r#"[{
+            "address": "FYjHNoFtSQ5uijKrZFyYAxvEr87hsKXkXcxkcmkBAf4r",
+            "amount": "771",
+            "decimals": 2,
+            "uiAmount": 7.71,
+            "uiAmountString": "7.71"
+        }]"#,
+    )
+    .unwrap();
+    assert_eq!(largest[0].amount, TokenQuantity::new(771, 2));
+
+    let accounts: OptionalContext<Vec<RpcKeyedAccount>> = serde_json::from_str("[]").unwrap();
+    assert!(accounts.into_value().is_empty());
+}
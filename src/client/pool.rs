@@ -0,0 +1,449 @@
+use crate::{
+    RpcError, RpcErrorKind, RpcRequest, RpcResponse, Slot, SolProbeError, SolProbeResult, Timer,
+    Transport, TransportFuture,
+};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use std::sync::{Mutex, MutexGuard};
+
+/// How `EndpointPool` picks the endpoint of a request among the healthy ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Routing {
+    /// Each endpoint in turn
+    #[default]
+    RoundRobin,
+    /// The endpoint with the lowest average latency, endpoints without requests yet first.
+    /// Latencies are only measured when the pool has a `Timer`.
+    LowestLatency,
+}
+
+/// What an `EndpointPool` knows about one of its endpoints
+#[derive(Clone, Debug, PartialEq)]
+pub struct EndpointStats {
+    pub url: String,
+    /// Whether requests are routed to the endpoint. Endpoints are ejected when they fail to
+    /// answer, report being unhealthy or lag behind the others, until a health check passes.
+    pub healthy: bool,
+    /// The slot of the last health check
+    pub slot: Option<Slot>,
+    /// An exponential moving average of the time taken by the endpoint to answer
+    pub latency: Option<Duration>,
+    pub requests: u64,
+    pub failures: u64,
+    pub last_error: Option<SolProbeError>,
+}
+
+impl EndpointStats {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            healthy: true,
+            slot: None,
+            latency: None,
+            requests: 0,
+            failures: 0,
+            last_error: None,
+        }
+    }
+
+    fn record(&mut self, elapsed: Option<Duration>, error: Option<&SolProbeError>) {
+        self.requests += 1;
+        if let Some(elapsed) = elapsed {
+            self.latency = Some(match self.latency {
+                Some(latency) => latency.mul_f64(0.8) + elapsed.mul_f64(0.2),
+                None => elapsed,
+            });
+        }
+
+        if let Some(error) = error {
+            self.failures += 1;
+            self.last_error = Some(error.clone());
+            // Rate limits say nothing about the health of the node
+            if !matches!(error, SolProbeError::Http { status: 429, .. }) {
+                self.healthy = false;
+            }
+        }
+    }
+}
+
+/// Spreads requests over several RPC endpoints and fails over to the next one when an
+/// endpoint cannot be reached or reports being unhealthy. Only requests that are safe to send
+/// twice fail over, as `RpcClient` tells through `Transport::post_request`, others only when
+/// the endpoint refused them unexecuted: rate limited, unhealthy or behind. It is a
+/// `Transport` itself, hand it to `RpcClient::new` whose URL it then ignores.
+pub struct EndpointPool<T: Transport> {
+    transport: T,
+    endpoints: Mutex<Vec<EndpointStats>>,
+    routing: Routing,
+    max_slot_lag: u64,
+    next: AtomicUsize,
+    timer: Option<Box<dyn Timer>>,
+}
+
+impl<T: Transport> EndpointPool<T> {
+    /// A pool of the endpoints at `urls`, all reached through `transport`
+    pub fn new<I: IntoIterator<Item = U>, U: Into<String>>(urls: I, transport: T) -> Self {
+        Self {
+            transport,
+            endpoints: Mutex::new(
+                urls.into_iter()
+                    .map(|url| EndpointStats::new(url.into()))
+                    .collect(),
+            ),
+            routing: Routing::default(),
+            max_slot_lag: 50,
+            next: AtomicUsize::new(0),
+            timer: None,
+        }
+    }
+
+    pub fn routing(&mut self, routing: Routing) -> &mut Self {
+        self.routing = routing;
+
+        self
+    }
+
+    /// How many slots an endpoint may be behind the most advanced one before it is ejected,
+    /// 50 by default
+    pub fn max_slot_lag(&mut self, max_slot_lag: u64) -> &mut Self {
+        self.max_slot_lag = max_slot_lag;
+
+        self
+    }
+
+    /// Measures the latency of the endpoints, the pool never reads the clock otherwise
+    pub fn timer<S: Timer + 'static>(&mut self, timer: S) -> &mut Self {
+        self.timer = Some(Box::new(timer));
+
+        self
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn stats(&self) -> Vec<EndpointStats> {
+        self.endpoints().clone()
+    }
+
+    /// Calls `getHealth` and `getSlot` on every endpoint, ejecting the unhealthy and lagging
+    /// ones and bringing the others back. Returns the number of healthy endpoints, call it
+    /// periodically.
+    pub async fn check_health(&self) -> usize {
+        let urls = self
+            .endpoints()
+            .iter()
+            .map(|endpoint| endpoint.url.clone())
+            .collect::<Vec<_>>();
+
+        let mut slots = Vec::with_capacity(urls.len());
+        for (index, url) in urls.iter().enumerate() {
+            let slot = match self
+                .health_request::<String>(index, url, RpcRequest::GetHealth)
+                .await
+            {
+                Ok(_) => {
+                    self.health_request::<Slot>(index, url, RpcRequest::GetSlot)
+                        .await
+                }
+                Err(error) => Err(error),
+            };
+            slots.push(slot.ok());
+        }
+
+        let highest = slots.iter().flatten().max().copied();
+        let mut endpoints = self.endpoints();
+        for (endpoint, slot) in endpoints.iter_mut().zip(slots) {
+            endpoint.slot = slot.or(endpoint.slot);
+            endpoint.healthy = match (slot, highest) {
+                (Some(slot), Some(highest)) => highest - slot <= self.max_slot_lag,
+                _ => false,
+            };
+        }
+
+        endpoints.iter().filter(|endpoint| endpoint.healthy).count()
+    }
+
+    async fn health_request<R: serde::de::DeserializeOwned>(
+        &self,
+        index: usize,
+        url: &str,
+        request: RpcRequest,
+    ) -> SolProbeResult<R> {
+        let body = request
+            .build_request_json(0, serde_json::Value::Null)
+            .to_string();
+
+        let start = self.now();
+        let response = self.transport.post(url, body).await;
+        let elapsed = self.elapsed(start);
+
+        let result = response.and_then(|response| {
+            serde_json::from_str::<RpcResponse<R>>(&response)
+                .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))?
+                .into_result()
+        });
+        if let Some(endpoint) = self.endpoints().get_mut(index) {
+            endpoint.record(elapsed, result.as_ref().err());
+        }
+
+        result
+    }
+
+    /// The indices of the endpoints in the order to try them, the healthy ones by `routing`
+    /// and then the ejected ones, as a last resort
+    fn order(&self) -> Vec<usize> {
+        let endpoints = self.endpoints();
+        let (mut healthy, ejected): (Vec<usize>, Vec<usize>) =
+            (0..endpoints.len()).partition(|index| endpoints[*index].healthy);
+
+        match self.routing {
+            Routing::RoundRobin if !healthy.is_empty() => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed) % healthy.len();
+                healthy.rotate_left(start);
+            }
+            Routing::RoundRobin => {}
+            Routing::LowestLatency => healthy.sort_by_key(|index| endpoints[*index].latency),
+        }
+
+        healthy.extend(ejected);
+        healthy
+    }
+
+    fn now(&self) -> Option<Duration> {
+        self.timer.as_ref().map(|timer| timer.now())
+    }
+
+    fn elapsed(&self, start: Option<Duration>) -> Option<Duration> {
+        Some(self.now()?.saturating_sub(start?))
+    }
+
+    fn endpoints(&self) -> MutexGuard<'_, Vec<EndpointStats>> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl<T: Transport> Transport for EndpointPool<T> {
+    fn post<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        self.post_request(url, body, false)
+    }
+
+    fn post_request<'a>(
+        &'a self,
+        _url: &'a str,
+        body: String,
+        idempotent: bool,
+    ) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut last = Err(SolProbeError::Transport(
+                "the endpoint pool is empty".into(),
+            ));
+
+            for index in self.order() {
+                let url = self.endpoints()[index].url.clone();
+
+                let start = self.now();
+                let result = self.transport.post(&url, body.clone()).await;
+                let (error, refused) = match &result {
+                    Ok(response) => match unhealthy_error(response) {
+                        Some((error, refused)) => (Some(SolProbeError::Rpc(error)), refused),
+                        None => (None, false),
+                    },
+                    Err(error) => (
+                        Some(error.clone()),
+                        matches!(error, SolProbeError::Http { status: 429, .. }),
+                    ),
+                };
+                self.endpoints()[index].record(self.elapsed(start), error.as_ref());
+
+                last = result;
+                if error.is_none() || !(idempotent || refused) {
+                    break;
+                }
+            }
+
+            last
+        })
+    }
+}
+
+/// The error of a response saying the node is unhealthy or behind, which the endpoint is
+/// ejected for like for a failure to answer, and whether the node refused every request of
+/// the body that way, executing none of them
+fn unhealthy_error(response: &str) -> Option<(RpcError, bool)> {
+    let responses = match serde_json::from_str::<serde_json::Value>(response).ok()? {
+        serde_json::Value::Array(responses) => responses,
+        response => vec![response],
+    };
+
+    let errors = responses
+        .iter()
+        .filter_map(|response| serde_json::from_value::<RpcError>(response["error"].clone()).ok())
+        .filter(|error| {
+            matches!(
+                error.kind(),
+                RpcErrorKind::NodeUnhealthy | RpcErrorKind::MinContextSlotNotReached
+            )
+        })
+        .collect::<Vec<_>>();
+    let refused = errors.len() == responses.len();
+
+    errors.into_iter().next().map(|error| (error, refused))
+}
+
+#[test]
+fn endpoint_pool_ejects_lagging_endpoints_and_fails_over() {
+    use crate::{Fixture, MockRpcServer, RpcClient, RpcError, SleepFuture};
+    use serde_json::json;
+    use std::cell::{Cell, RefCell};
+
+    const SIGNATURE: &str =
+        "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+
+    /// Routes each request to the server of its endpoint
+    struct Providers {
+        servers: Vec<(&'static str, MockRpcServer)>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl Providers {
+        fn server(&self, url: &str) -> &MockRpcServer {
+            &self
+                .servers
+                .iter()
+                .find(|(name, _)| *name == url)
+                .unwrap()
+                .1
+        }
+    }
+
+    impl Transport for Providers {
+        fn post<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+            self.calls.borrow_mut().push(url.to_string());
+
+            self.server(url).post(url, body)
+        }
+    }
+
+    // `a` is ahead, `b` lags 100 slots behind and `c` is unhealthy
+    let servers = [("a", Some(1000)), ("b", Some(900)), ("c", None)]
+        .iter()
+        .map(|&(url, slot)| {
+            let mut server = MockRpcServer::new();
+            match slot {
+                Some(slot) => server
+                    .result("getHealth", json!([]), json!("ok"))
+                    .result("getSlot", json!([]), json!(slot))
+                    .result("sendTransaction", json!(["AQ=="]), json!(SIGNATURE)),
+                None => server.insert(Fixture {
+                    method: "getHealth".to_string(),
+                    params: json!([]),
+                    response: json!({
+                        "jsonrpc": "2.0",
+                        "error": { "code": -32005, "message": "Node is unhealthy" },
+                        "id": 0
+                    }),
+                }),
+            };
+
+            (url, server)
+        })
+        .collect();
+    let providers = Providers {
+        servers,
+        calls: RefCell::default(),
+    };
+
+    /// A clock that moves a millisecond every time it is read
+    #[derive(Default)]
+    struct Ticker(Cell<u64>);
+
+    impl Timer for Ticker {
+        fn sleep(&self, _duration: Duration) -> SleepFuture<'_> {
+            Box::pin(async {})
+        }
+
+        fn now(&self) -> Duration {
+            self.0.set(self.0.get() + 1);
+            Duration::from_millis(self.0.get())
+        }
+    }
+
+    let mut pool = EndpointPool::new(vec!["a", "b", "c"], providers);
+    pool.timer(Ticker::default());
+    let client = RpcClient::new("", pool);
+
+    smol::block_on(async {
+        let pool = client.transport();
+        assert_eq!(pool.check_health().await, 1);
+        let healthy = pool
+            .stats()
+            .into_iter()
+            .map(|endpoint| (endpoint.url, endpoint.healthy, endpoint.slot))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            healthy,
+            [
+                ("a".to_string(), true, Some(1000)),
+                ("b".to_string(), false, Some(900)),
+                ("c".to_string(), false, None),
+            ]
+        );
+
+        pool.transport().calls.borrow_mut().clear();
+        assert_eq!(client.get_slot().await, Ok(1000));
+        assert_eq!(*pool.transport().calls.borrow(), ["a"]);
+
+        let server = pool.transport().server("a");
+        server.fail(SolProbeError::Transport("connection refused".into()));
+        assert_eq!(client.get_slot().await, Ok(900));
+        assert_eq!(*pool.transport().calls.borrow(), ["a", "a", "b"]);
+
+        let a = &pool.stats()[0];
+        assert!(!a.healthy);
+        assert_eq!((a.requests, a.failures), (4, 1));
+        assert_eq!(a.latency, Some(Duration::from_millis(1)));
+
+        // A transaction may have reached the node, it is not sent to another one
+        pool.transport().calls.borrow_mut().clear();
+        server.fail(SolProbeError::Transport("connection refused".into()));
+        assert_eq!(
+            client
+                .send::<_, String>(RpcRequest::SendTransaction, ("AQ==",))
+                .await,
+            Err(SolProbeError::Transport("connection refused".into()))
+        );
+        assert_eq!(*pool.transport().calls.borrow(), ["a"]);
+
+        // Unless the node refused it without executing it
+        server.error(RpcError {
+            code: -32005,
+            message: "Node is unhealthy".to_string(),
+            data: None,
+        });
+        pool.transport().calls.borrow_mut().clear();
+        assert_eq!(
+            client
+                .send::<_, String>(RpcRequest::SendTransaction, ("AQ==",))
+                .await,
+            Ok(SIGNATURE.to_string())
+        );
+        assert_eq!(*pool.transport().calls.borrow(), ["a", "b"]);
+
+        // An endpoint answering that it is behind is ejected and the request fails over
+        server.error(RpcError {
+            code: -32016,
+            message: "Minimum context slot has not been reached".to_string(),
+            data: None,
+        });
+        pool.transport().calls.borrow_mut().clear();
+        assert_eq!(client.get_slot().await, Ok(900));
+        assert_eq!(*pool.transport().calls.borrow(), ["a", "b"]);
+        assert!(matches!(
+            &pool.stats()[0].last_error,
+            Some(SolProbeError::Rpc(error)) if error.kind() == RpcErrorKind::MinContextSlotNotReached
+        ));
+    });
+}
@@ -0,0 +1,581 @@
+use crate::{
+    AccountInfoConfig, BlockSubscribeConfig, BlockSubscribeFilter, CommitmentConfig, LogsFilter,
+    ProgramAccountsConfig, Pubkey, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse, RpcRequest,
+    RpcResponse, RpcSignatureResult, Signature, SignatureSubscribeConfig, SlotInfo, SolProbeError,
+    SolProbeResult, UiAccount, WithContext, WsConnection, WsConnector,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, VecDeque};
+
+/// A subscription of a `PubsubClient`. Unlike the id the node hands out, it stays the same
+/// when the client reconnects and subscribes again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubscriptionId(u64);
+
+/// The subscriptions of the pubsub API
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubscriptionKind {
+    Account,
+    Block,
+    Logs,
+    Program,
+    Signature,
+    Slot,
+}
+
+impl SubscriptionKind {
+    const ALL: [SubscriptionKind; 6] = [
+        SubscriptionKind::Account,
+        SubscriptionKind::Block,
+        SubscriptionKind::Logs,
+        SubscriptionKind::Program,
+        SubscriptionKind::Signature,
+        SubscriptionKind::Slot,
+    ];
+
+    pub fn subscribe_method(self) -> &'static str {
+        match self {
+            SubscriptionKind::Account => "accountSubscribe",
+            SubscriptionKind::Block => "blockSubscribe",
+            SubscriptionKind::Logs => "logsSubscribe",
+            SubscriptionKind::Program => "programSubscribe",
+            SubscriptionKind::Signature => "signatureSubscribe",
+            SubscriptionKind::Slot => "slotSubscribe",
+        }
+    }
+
+    pub fn unsubscribe_method(self) -> &'static str {
+        match self {
+            SubscriptionKind::Account => "accountUnsubscribe",
+            SubscriptionKind::Block => "blockUnsubscribe",
+            SubscriptionKind::Logs => "logsUnsubscribe",
+            SubscriptionKind::Program => "programUnsubscribe",
+            SubscriptionKind::Signature => "signatureUnsubscribe",
+            SubscriptionKind::Slot => "slotUnsubscribe",
+        }
+    }
+
+    pub fn notification_method(self) -> &'static str {
+        match self {
+            SubscriptionKind::Account => "accountNotification",
+            SubscriptionKind::Block => "blockNotification",
+            SubscriptionKind::Logs => "logsNotification",
+            SubscriptionKind::Program => "programNotification",
+            SubscriptionKind::Signature => "signatureNotification",
+            SubscriptionKind::Slot => "slotNotification",
+        }
+    }
+}
+
+/// The typed payload of a notification
+#[derive(Debug, PartialEq)]
+pub enum Notification {
+    Account(WithContext<UiAccount>),
+    Block(WithContext<RpcBlockUpdate>),
+    Logs(WithContext<RpcLogsResponse>),
+    Program(WithContext<RpcKeyedAccount>),
+    Signature(WithContext<RpcSignatureResult>),
+    Slot(SlotInfo),
+}
+
+impl Notification {
+    fn parse(kind: SubscriptionKind, result: serde_json::Value) -> serde_json::Result<Self> {
+        Ok(match kind {
+            SubscriptionKind::Account => Notification::Account(serde_json::from_value(result)?),
+            SubscriptionKind::Block => Notification::Block(serde_json::from_value(result)?),
+            SubscriptionKind::Logs => Notification::Logs(serde_json::from_value(result)?),
+            SubscriptionKind::Program => Notification::Program(serde_json::from_value(result)?),
+            SubscriptionKind::Signature => Notification::Signature(serde_json::from_value(result)?),
+            SubscriptionKind::Slot => Notification::Slot(serde_json::from_value(result)?),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PubsubNotification {
+    pub subscription: SubscriptionId,
+    pub notification: Notification,
+}
+
+#[derive(Deserialize)]
+struct RawNotification {
+    method: String,
+    params: RawNotificationParams,
+}
+
+#[derive(Deserialize)]
+struct RawNotificationParams {
+    result: serde_json::Value,
+    subscription: u64,
+}
+
+struct ActiveSubscription {
+    kind: SubscriptionKind,
+    params: serde_json::Value,
+    /// The id the node handed out on the current connection
+    node_id: u64,
+}
+
+/// A client of a node's WebSocket pubsub API. It connects on the first subscription and,
+/// when the connection drops, reconnects and subscribes again from `next`.
+pub struct PubsubClient<C: WsConnector> {
+    url: String,
+    connector: C,
+    connection: Option<C::Connection>,
+    reconnect: bool,
+    max_reconnects: u32,
+    next_id: u64,
+    next_subscription: u64,
+    subscriptions: BTreeMap<SubscriptionId, ActiveSubscription>,
+    /// Messages received while waiting for the answer to a request
+    pending: VecDeque<String>,
+}
+
+impl<C: WsConnector> PubsubClient<C> {
+    /// A client for the pubsub endpoint at `url`, for example `wss://api.devnet.solana.com`
+    pub fn new<U: Into<String>>(url: U, connector: C) -> Self {
+        Self {
+            url: url.into(),
+            connector,
+            connection: None,
+            reconnect: true,
+            max_reconnects: 5,
+            next_id: 1,
+            next_subscription: 1,
+            subscriptions: BTreeMap::default(),
+            pending: VecDeque::default(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn connector(&self) -> &C {
+        &self.connector
+    }
+
+    /// Whether `next` reconnects and subscribes again when the connection drops, on by default
+    pub fn reconnect(&mut self, reconnect: bool) -> &mut Self {
+        self.reconnect = reconnect;
+
+        self
+    }
+
+    /// How many times a single call to `next` reconnects before it gives up, 5 by default
+    pub fn max_reconnects(&mut self, max_reconnects: u32) -> &mut Self {
+        self.max_reconnects = max_reconnects;
+
+        self
+    }
+
+    pub fn subscriptions(&self) -> impl Iterator<Item = SubscriptionId> + '_ {
+        self.subscriptions.keys().copied()
+    }
+
+    pub async fn account_subscribe(
+        &mut self,
+        pubkey: Pubkey,
+        config: Option<AccountInfoConfig>,
+    ) -> SolProbeResult<SubscriptionId> {
+        self.subscribe(
+            SubscriptionKind::Account,
+            serde_json::json!([pubkey, config]),
+        )
+        .await
+    }
+
+    pub async fn block_subscribe(
+        &mut self,
+        filter: BlockSubscribeFilter,
+        config: Option<BlockSubscribeConfig>,
+    ) -> SolProbeResult<SubscriptionId> {
+        self.subscribe(SubscriptionKind::Block, serde_json::json!([filter, config]))
+            .await
+    }
+
+    pub async fn logs_subscribe(
+        &mut self,
+        filter: LogsFilter,
+        config: Option<CommitmentConfig>,
+    ) -> SolProbeResult<SubscriptionId> {
+        self.subscribe(SubscriptionKind::Logs, serde_json::json!([filter, config]))
+            .await
+    }
+
+    pub async fn program_subscribe(
+        &mut self,
+        program_id: Pubkey,
+        config: Option<ProgramAccountsConfig>,
+    ) -> SolProbeResult<SubscriptionId> {
+        self.subscribe(
+            SubscriptionKind::Program,
+            serde_json::json!([program_id, config]),
+        )
+        .await
+    }
+
+    pub async fn signature_subscribe(
+        &mut self,
+        signature: Signature,
+        config: Option<SignatureSubscribeConfig>,
+    ) -> SolProbeResult<SubscriptionId> {
+        self.subscribe(
+            SubscriptionKind::Signature,
+            serde_json::json!([signature, config]),
+        )
+        .await
+    }
+
+    pub async fn slot_subscribe(&mut self) -> SolProbeResult<SubscriptionId> {
+        self.subscribe(SubscriptionKind::Slot, serde_json::json!([]))
+            .await
+    }
+
+    /// Subscribes with `params` already serialized to a JSON array
+    pub async fn subscribe(
+        &mut self,
+        kind: SubscriptionKind,
+        params: serde_json::Value,
+    ) -> SolProbeResult<SubscriptionId> {
+        if self.connection.is_none() {
+            self.connect().await?;
+        }
+
+        let node_id = self
+            .request(kind.subscribe_method(), params.clone())
+            .await?;
+        let subscription = SubscriptionId(self.next_subscription);
+        self.next_subscription += 1;
+        self.subscriptions.insert(
+            subscription,
+            ActiveSubscription {
+                kind,
+                params,
+                node_id,
+            },
+        );
+
+        Ok(subscription)
+    }
+
+    /// Ends the subscription, returning whether the node still knew about it
+    pub async fn unsubscribe(&mut self, subscription: SubscriptionId) -> SolProbeResult<bool> {
+        let active = self
+            .subscriptions
+            .remove(&subscription)
+            .ok_or(SolProbeError::InvalidRequest)?;
+
+        if self.connection.is_none() {
+            return Ok(false);
+        }
+
+        self.request(
+            active.kind.unsubscribe_method(),
+            serde_json::json!([active.node_id]),
+        )
+        .await
+    }
+
+    /// Waits for the next notification of any subscription. A signature subscription ends
+    /// with its `Processed` notification, as the node drops it after sending it.
+    pub async fn next(&mut self) -> SolProbeResult<PubsubNotification> {
+        let mut reconnects = 0;
+
+        loop {
+            let message = match self.pending.pop_front() {
+                Some(message) => message,
+                None => match self.receive().await {
+                    Ok(Some(message)) => message,
+                    Ok(None) | Err(SolProbeError::Transport(_))
+                        if self.reconnect && reconnects < self.max_reconnects =>
+                    {
+                        reconnects += 1;
+                        self.restore().await?;
+                        continue;
+                    }
+                    Ok(None) => return Err(SolProbeError::Transport("connection closed".into())),
+                    Err(error) => return Err(error),
+                },
+            };
+
+            if let Some(notification) = self.parse_notification(&message)? {
+                if let Notification::Signature(WithContext {
+                    value: RpcSignatureResult::Processed { .. },
+                    ..
+                }) = notification.notification
+                {
+                    self.subscriptions.remove(&notification.subscription);
+                }
+
+                return Ok(notification);
+            }
+        }
+    }
+
+    async fn connect(&mut self) -> SolProbeResult<()> {
+        self.pending.clear();
+        self.connection = None;
+        self.connection = Some(self.connector.connect(&self.url).await?);
+
+        Ok(())
+    }
+
+    /// Reconnects and subscribes again to everything, with the params of the first time
+    async fn restore(&mut self) -> SolProbeResult<()> {
+        self.connect().await?;
+
+        let subscriptions = self
+            .subscriptions
+            .iter()
+            .map(|(id, active)| (*id, active.kind, active.params.clone()))
+            .collect::<Vec<_>>();
+        for (id, kind, params) in subscriptions {
+            let node_id = self.request(kind.subscribe_method(), params).await?;
+            if let Some(active) = self.subscriptions.get_mut(&id) {
+                active.node_id = node_id;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> SolProbeResult<Option<String>> {
+        match self.connection.as_mut() {
+            Some(connection) => connection.recv().await,
+            None => Ok(None),
+        }
+    }
+
+    /// Sends a request and waits for its answer, keeping the notifications received meanwhile
+    async fn request<R: serde::de::DeserializeOwned>(
+        &mut self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> SolProbeResult<R> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let body = RpcRequest::Custom { method }
+            .build_request_json(id, params)
+            .to_string();
+
+        let connection = self
+            .connection
+            .as_mut()
+            .ok_or_else(|| SolProbeError::Transport("not connected".into()))?;
+        connection.send(body).await?;
+
+        loop {
+            let message = connection
+                .recv()
+                .await?
+                .ok_or_else(|| SolProbeError::Transport("connection closed".into()))?;
+
+            match serde_json::from_str::<RpcResponse<serde_json::Value>>(&message) {
+                Ok(response) if response.id == Some(id) => {
+                    return serde_json::from_value(response.into_result()?)
+                        .map_err(|error| SolProbeError::InvalidResponse(error.to_string()));
+                }
+                _ => self.pending.push_back(message),
+            }
+        }
+    }
+
+    /// The notification in `message`, `None` for other messages and for subscriptions that
+    /// were dropped since
+    fn parse_notification(&self, message: &str) -> SolProbeResult<Option<PubsubNotification>> {
+        let raw = match serde_json::from_str::<RawNotification>(message) {
+            Ok(raw) => raw,
+            Err(_) => return Ok(None),
+        };
+
+        let kind = SubscriptionKind::ALL
+            .iter()
+            .find(|kind| kind.notification_method() == raw.method);
+        let subscription = self
+            .subscriptions
+            .iter()
+            .find(|(_, active)| active.node_id == raw.params.subscription);
+
+        match (kind, subscription) {
+            (Some(kind), Some((id, active))) if active.kind == *kind => {
+                let notification = Notification::parse(*kind, raw.params.result)
+                    .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))?;
+
+                Ok(Some(PubsubNotification {
+                    subscription: *id,
+                    notification,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[test]
+fn pubsub_resubscribes_after_reconnecting() {
+    use crate::WsFuture;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Answers subscriptions with ids counting up from `first_id`, then plays `script` and
+    /// closes the connection
+    struct MockNode {
+        scripts: RefCell<VecDeque<(u64, Vec<&'static str>)>>,
+        requests: Rc<RefCell<Vec<serde_json::Value>>>,
+    }
+
+    struct MockConnection {
+        inbox: VecDeque<String>,
+        next_id: u64,
+        requests: Rc<RefCell<Vec<serde_json::Value>>>,
+    }
+
+    impl WsConnector for MockNode {
+        type Connection = MockConnection;
+
+        fn connect<'a>(&'a self, _url: &'a str) -> WsFuture<'a, MockConnection> {
+            let connection = self
+                .scripts
+                .borrow_mut()
+                .pop_front()
+                .map(|(first_id, script)| MockConnection {
+                    inbox: script.into_iter().map(String::from).collect(),
+                    next_id: first_id,
+                    requests: self.requests.clone(),
+                });
+
+            Box::pin(async {
+                connection.ok_or_else(|| SolProbeError::Transport("connection refused".into()))
+            })
+        }
+    }
+
+    impl WsConnection for MockConnection {
+        fn send(&mut self, text: String) -> WsFuture<'_, ()> {
+            let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+            let result = if request["method"].as_str().unwrap().ends_with("Unsubscribe") {
+                serde_json::json!(true)
+            } else if request["params"] == serde_json::json!(["malformed"]) {
+                serde_json::json!("malformed")
+            } else {
+                self.next_id += 1;
+                serde_json::json!(self.next_id - 1)
+            };
+            self.inbox.push_back(
+                serde_json::json!({ "jsonrpc": "2.0", "result": result, "id": request["id"] })
+                    .to_string(),
+            );
+            self.requests.borrow_mut().push(request);
+
+            Box::pin(async { Ok(()) })
+        }
+
+        fn recv(&mut self) -> WsFuture<'_, Option<String>> {
+            let message = self.inbox.pop_front();
+
+            Box::pin(async { Ok(message) })
+        }
+    }
+
+    let node = MockNode {
+        scripts: RefCell::new(VecDeque::from(vec![
+            (
+                10,
+                vec![
+                    r#"{"jsonrpc":"2.0","method":"slotNotification","params":{"result":{"slot":5,"parent":4,"root":0},"subscription":10}}"#,
+                ],
+            ),
+            (
+                20,
+                vec![
+                    r#"{"jsonrpc":"2.0","method":"slotNotification","params":{"result":{"slot":6,"parent":5,"root":0},"subscription":20}}"#,
+                    r#"{"jsonrpc":"2.0","method":"signatureNotification","params":{"result":{"context":{"slot":6},"value":{"err":null}},"subscription":21}}"#,
+                ],
+            ),
+        ])),
+        requests: Rc::default(),
+    };
+    let mut client = PubsubClient::new("ws://localhost:8900", node);
+
+    smol::block_on(async {
+        let slots = client.slot_subscribe().await.unwrap();
+        let signature = client
+            .signature_subscribe(Signature::default(), None)
+            .await
+            .unwrap();
+
+        let first = client.next().await.unwrap();
+        assert_eq!(first.subscription, slots);
+        assert!(matches!(
+            first.notification,
+            Notification::Slot(SlotInfo { slot: 5, .. })
+        ));
+
+        // The first connection is closed by now, the client reconnects and gets new node ids
+        let second = client.next().await.unwrap();
+        assert_eq!(second.subscription, slots);
+        assert!(matches!(
+            second.notification,
+            Notification::Slot(SlotInfo { slot: 6, .. })
+        ));
+        assert_eq!(
+            client.next().await.unwrap(),
+            PubsubNotification {
+                subscription: signature,
+                notification: Notification::Signature(WithContext {
+                    context: crate::RpcResponseContext {
+                        slot: 6,
+                        api_version: None,
+                    },
+                    value: RpcSignatureResult::Processed { err: None },
+                }),
+            }
+        );
+
+        // The node dropped the signature subscription along with its last notification
+        assert_eq!(client.subscriptions().collect::<Vec<_>>(), [slots]);
+        assert_eq!(client.unsubscribe(slots).await, Ok(true));
+        assert!(client.next().await.is_err());
+    });
+
+    let requests = client.connector().requests.borrow();
+    let methods = requests
+        .iter()
+        .map(|request| request["method"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        methods,
+        [
+            "slotSubscribe",
+            "signatureSubscribe",
+            "slotSubscribe",
+            "signatureSubscribe",
+            "slotUnsubscribe",
+        ]
+    );
+    assert_eq!(requests[4]["params"], serde_json::json!([20]));
+
+    // A node that keeps closing the connection is given up on
+    let node = MockNode {
+        scripts: RefCell::new((0..10).map(|_| (1, Vec::new())).collect()),
+        requests: Rc::default(),
+    };
+    let mut client = PubsubClient::new("ws://localhost:8900", node);
+    client.max_reconnects(3);
+    smol::block_on(async {
+        // An answer of the wrong type fails the request instead of being waited past
+        assert!(matches!(
+            client
+                .subscribe(SubscriptionKind::Slot, serde_json::json!(["malformed"]))
+                .await,
+            Err(SolProbeError::InvalidResponse(_))
+        ));
+        client.slot_subscribe().await.unwrap();
+        assert_eq!(
+            client.next().await,
+            Err(SolProbeError::Transport("connection closed".into()))
+        );
+    });
+    assert_eq!(client.connector().scripts.borrow().len(), 6);
+}
@@ -0,0 +1,187 @@
+use crate::{SolProbeError, SolProbeResult};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A JSON-RPC 2.0 response, holding either the `result` of the call or an `error`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound(deserialize = "T: Deserialize<'de>"))]
+pub struct RpcResponse<T> {
+    pub jsonrpc: String,
+    /// The id of the request, `None` when the node could not parse the request
+    pub id: Option<u64>,
+    /// Present even when the method returned `null`, in which case `T` must be an `Option`
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub result: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl<T> RpcResponse<T> {
+    /// The result of the call, `SolProbeError::Rpc` when the node answered with an error
+    pub fn into_result(self) -> SolProbeResult<T> {
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(SolProbeError::Rpc(error)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(SolProbeError::InvalidResponse(
+                "neither a result nor an error".to_string(),
+            )),
+        }
+    }
+}
+
+/// Keeps a `null` result apart from a missing one, serde would otherwise read both as `None`
+fn present<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    T::deserialize(deserializer).map(Some)
+}
+
+/// The `error` object of a JSON-RPC response
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    /// Extra details some errors carry, like the simulation result of a failed preflight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl RpcError {
+    pub fn kind(&self) -> RpcErrorKind {
+        RpcErrorKind::from_code(self.code)
+    }
+}
+
+/// The error codes defined by JSON-RPC 2.0 and the custom server errors of Solana nodes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcErrorKind {
+    ParseError,
+    InvalidRequest,
+    MethodNotFound,
+    InvalidParams,
+    InternalError,
+    /// The block was cleaned up from the node's ledger
+    BlockCleanedUp,
+    SendTransactionPreflightFailure,
+    TransactionSignatureVerificationFailure,
+    /// The block is not available yet or has been purged
+    BlockNotAvailable,
+    NodeUnhealthy,
+    TransactionPrecompileVerificationFailure,
+    /// No block was produced for the slot
+    SlotSkipped,
+    NoSnapshot,
+    /// No block was produced for the slot according to the long-term storage
+    LongTermStorageSlotSkipped,
+    KeyExcludedFromSecondaryIndex,
+    TransactionHistoryNotAvailable,
+    ScanError,
+    TransactionSignatureLenMismatch,
+    BlockStatusNotAvailableYet,
+    UnsupportedTransactionVersion,
+    MinContextSlotNotReached,
+    EpochRewardsPeriodActive,
+    SlotNotEpochBoundary,
+    LongTermStorageUnreachable,
+    /// A code this crate does not know about
+    Other(i64),
+}
+
+impl RpcErrorKind {
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            -32700 => RpcErrorKind::ParseError,
+            -32600 => RpcErrorKind::InvalidRequest,
+            -32601 => RpcErrorKind::MethodNotFound,
+            -32602 => RpcErrorKind::InvalidParams,
+            -32603 => RpcErrorKind::InternalError,
+            -32001 => RpcErrorKind::BlockCleanedUp,
+            -32002 => RpcErrorKind::SendTransactionPreflightFailure,
+            -32003 => RpcErrorKind::TransactionSignatureVerificationFailure,
+            -32004 => RpcErrorKind::BlockNotAvailable,
+            -32005 => RpcErrorKind::NodeUnhealthy,
+            -32006 => RpcErrorKind::TransactionPrecompileVerificationFailure,
+            -32007 => RpcErrorKind::SlotSkipped,
+            -32008 => RpcErrorKind::NoSnapshot,
+            -32009 => RpcErrorKind::LongTermStorageSlotSkipped,
+            -32010 => RpcErrorKind::KeyExcludedFromSecondaryIndex,
+            -32011 => RpcErrorKind::TransactionHistoryNotAvailable,
+            -32012 => RpcErrorKind::ScanError,
+            -32013 => RpcErrorKind::TransactionSignatureLenMismatch,
+            -32014 => RpcErrorKind::BlockStatusNotAvailableYet,
+            -32015 => RpcErrorKind::UnsupportedTransactionVersion,
+            -32016 => RpcErrorKind::MinContextSlotNotReached,
+            -32017 => RpcErrorKind::EpochRewardsPeriodActive,
+            -32018 => RpcErrorKind::SlotNotEpochBoundary,
+            -32019 => RpcErrorKind::LongTermStorageUnreachable,
+            code => RpcErrorKind::Other(code),
+        }
+    }
+
+    pub fn code(self) -> i64 {
+        match self {
+            RpcErrorKind::ParseError => -32700,
+            RpcErrorKind::InvalidRequest => -32600,
+            RpcErrorKind::MethodNotFound => -32601,
+            RpcErrorKind::InvalidParams => -32602,
+            RpcErrorKind::InternalError => -32603,
+            RpcErrorKind::BlockCleanedUp => -32001,
+            RpcErrorKind::SendTransactionPreflightFailure => -32002,
+            RpcErrorKind::TransactionSignatureVerificationFailure => -32003,
+            RpcErrorKind::BlockNotAvailable => -32004,
+            RpcErrorKind::NodeUnhealthy => -32005,
+            RpcErrorKind::TransactionPrecompileVerificationFailure => -32006,
+            RpcErrorKind::SlotSkipped => -32007,
+            RpcErrorKind::NoSnapshot => -32008,
+            RpcErrorKind::LongTermStorageSlotSkipped => -32009,
+            RpcErrorKind::KeyExcludedFromSecondaryIndex => -32010,
+            RpcErrorKind::TransactionHistoryNotAvailable => -32011,
+            RpcErrorKind::ScanError => -32012,
+            RpcErrorKind::TransactionSignatureLenMismatch => -32013,
+            RpcErrorKind::BlockStatusNotAvailableYet => -32014,
+            RpcErrorKind::UnsupportedTransactionVersion => -32015,
+            RpcErrorKind::MinContextSlotNotReached => -32016,
+            RpcErrorKind::EpochRewardsPeriodActive => -32017,
+            RpcErrorKind::SlotNotEpochBoundary => -32018,
+            RpcErrorKind::LongTermStorageUnreachable => -32019,
+            RpcErrorKind::Other(code) => code,
+        }
+    }
+}
+
+#[test]
+fn rpc_response_separates_results_from_errors() {
+    let skipped: RpcResponse<crate::EncodedConfirmedBlock> = serde_json::from_str(
+        r#"{
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": {
+                "code": -32007,
+                "message": "Slot 1 was skipped, or missing due to ledger jump to recent snapshot"
+            }
+        }"#,
+    )
+    .unwrap();
+    match skipped.into_result() {
+        Err(SolProbeError::Rpc(error)) => assert_eq!(error.kind(), RpcErrorKind::SlotSkipped),
+        other => panic!("expected an RPC error, got {:?}", other),
+    }
+
+    let block_time: RpcResponse<Option<i64>> =
+        serde_json::from_str(r#"{ "jsonrpc": "2.0", "id": 2, "result": null }"#).unwrap();
+    assert_eq!(block_time.into_result(), Ok(None));
+
+    let empty: RpcResponse<u64> = serde_json::from_str(r#"{ "jsonrpc": "2.0", "id": 3 }"#).unwrap();
+    assert_eq!(
+        empty.into_result(),
+        Err(SolProbeError::InvalidResponse(
+            "neither a result nor an error".to_string()
+        ))
+    );
+    assert_eq!(RpcErrorKind::from_code(-32099), RpcErrorKind::Other(-32099));
+}
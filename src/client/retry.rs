@@ -0,0 +1,316 @@
+use crate::{RpcErrorKind, RpcRequest, SolProbeError};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::collections::HashMap;
+
+/// The future returned by `Timer::sleep`
+pub type SleepFuture<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// Waits for backoff delays and rate limits and reads the clock they are measured with. Like
+/// `Transport` it is left to the runtime the application uses, `smol::Timer::after` or
+/// `tokio::time::sleep` for example, so that no clock is read where there is none, like on
+/// `wasm32-unknown-unknown`.
+pub trait Timer {
+    fn sleep(&self, duration: Duration) -> SleepFuture<'_>;
+
+    /// The time elapsed since a fixed point of the timer's choosing, which must never go back,
+    /// for example `Instant::now() - start` or `performance.now()` in a browser
+    fn now(&self) -> Duration;
+}
+
+/// When `RpcClient` sends a failed request again and how long it waits in between
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one, `1` disables retries
+    pub max_attempts: u32,
+    /// The wait before the first retry, doubled for every further one
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Waits a random duration between half and all of the backoff, so that clients
+    /// throttled together do not retry together
+    pub jitter: bool,
+    /// Waits as long as the `Retry-After` header of a 429 or 503 asks instead of backing off
+    pub respect_retry_after: bool,
+    idempotent: HashMap<RpcRequest, bool>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            respect_retry_after: true,
+            idempotent: HashMap::default(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_attempts(&mut self, max_attempts: u32) -> &mut Self {
+        self.max_attempts = max_attempts;
+
+        self
+    }
+
+    pub fn base_delay(&mut self, base_delay: Duration) -> &mut Self {
+        self.base_delay = base_delay;
+
+        self
+    }
+
+    pub fn max_delay(&mut self, max_delay: Duration) -> &mut Self {
+        self.max_delay = max_delay;
+
+        self
+    }
+
+    pub fn jitter(&mut self, jitter: bool) -> &mut Self {
+        self.jitter = jitter;
+
+        self
+    }
+
+    pub fn respect_retry_after(&mut self, respect_retry_after: bool) -> &mut Self {
+        self.respect_retry_after = respect_retry_after;
+
+        self
+    }
+
+    /// Overrides `RpcRequest::is_idempotent` for `request`, for example to retry
+    /// `SendTransaction` when the transaction is signed with a durable nonce
+    pub fn idempotent(&mut self, request: RpcRequest, idempotent: bool) -> &mut Self {
+        self.idempotent.insert(request, idempotent);
+
+        self
+    }
+
+    pub fn is_idempotent(&self, request: RpcRequest) -> bool {
+        self.idempotent
+            .get(&request)
+            .copied()
+            .unwrap_or_else(|| request.is_idempotent())
+    }
+
+    /// Whether the requests failing with `error` may be sent again. Rate limited requests
+    /// never reached the node and always may, other failures only when every request is
+    /// idempotent since the node may have executed them.
+    pub fn should_retry(&self, requests: &[RpcRequest], error: &SolProbeError) -> bool {
+        let idempotent = || requests.iter().all(|request| self.is_idempotent(*request));
+
+        match error {
+            SolProbeError::Http { status: 429, .. } => true,
+            SolProbeError::Http { status, .. } => {
+                matches!(status, 500 | 502 | 503 | 504) && idempotent()
+            }
+            SolProbeError::Transport(_) => idempotent(),
+            SolProbeError::Rpc(error) => {
+                matches!(
+                    error.kind(),
+                    RpcErrorKind::NodeUnhealthy | RpcErrorKind::MinContextSlotNotReached
+                ) && idempotent()
+            }
+            _ => false,
+        }
+    }
+
+    /// The wait before sending again after `attempt` failed with `error`, counting from 0.
+    /// `random` is a number in `[0, 1)` that spreads the jitter.
+    pub fn delay(&self, attempt: u32, error: &SolProbeError, random: f64) -> Duration {
+        if let SolProbeError::Http {
+            retry_after: Some(seconds),
+            ..
+        } = error
+        {
+            if self.respect_retry_after {
+                return Duration::from_secs(*seconds).min(self.max_delay);
+            }
+        }
+
+        let backoff = self
+            .base_delay
+            .checked_mul(1 << attempt.min(31))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        if self.jitter {
+            backoff / 2 + backoff.mul_f64(random.clamp(0.0, 1.0) / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Limits the requests `RpcClient` sends with a token bucket, `burst` requests may be sent at
+/// once after which they are spread to `requests_per_second`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    limit: RateLimit,
+    /// May fall below zero, for requests that are waiting for their token
+    tokens: f64,
+    /// `Timer::now` when the tokens were last refilled, `None` until the first request
+    refilled: Option<Duration>,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.burst.max(1)),
+            refilled: None,
+        }
+    }
+
+    /// Takes a token and returns how long to wait until it is actually available
+    pub(crate) fn reserve(&mut self, now: Duration) -> Duration {
+        let capacity = f64::from(self.limit.burst.max(1));
+        let elapsed = self
+            .refilled
+            .map_or(0.0, |refilled| now.saturating_sub(refilled).as_secs_f64());
+
+        self.tokens = (self.tokens + elapsed * self.limit.requests_per_second).min(capacity);
+        self.refilled = Some(now);
+        self.tokens -= 1.0;
+
+        if self.tokens >= 0.0 || self.limit.requests_per_second <= 0.0 {
+            Duration::default()
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.limit.requests_per_second)
+        }
+    }
+}
+
+/// A SplitMix64 step over `state`, scaled to `[0, 1)`, good enough to spread retries
+pub(crate) fn next_random(state: &AtomicU64) -> f64 {
+    let mut z = state
+        .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[test]
+fn retry_policy_backs_off_and_limits() {
+    let mut policy = RetryPolicy::new();
+    policy.jitter(false);
+
+    let unavailable = SolProbeError::Http {
+        status: 503,
+        retry_after: None,
+    };
+    assert_eq!(
+        policy.delay(0, &unavailable, 0.5),
+        Duration::from_millis(250)
+    );
+    assert_eq!(policy.delay(3, &unavailable, 0.5), Duration::from_secs(2));
+    assert_eq!(policy.delay(40, &unavailable, 0.5), Duration::from_secs(10));
+
+    let throttled = SolProbeError::Http {
+        status: 429,
+        retry_after: Some(3),
+    };
+    assert_eq!(policy.delay(0, &throttled, 0.5), Duration::from_secs(3));
+    let throttled_long = SolProbeError::Http {
+        status: 429,
+        retry_after: Some(3600),
+    };
+    assert_eq!(
+        policy.delay(0, &throttled_long, 0.5),
+        Duration::from_secs(10)
+    );
+
+    assert!(policy.should_retry(&[RpcRequest::GetSlot], &unavailable));
+    assert!(!policy.should_retry(&[RpcRequest::SendTransaction], &unavailable));
+    assert!(policy.should_retry(&[RpcRequest::SendTransaction], &throttled));
+    policy.idempotent(RpcRequest::SendTransaction, true);
+    assert!(policy.should_retry(&[RpcRequest::SendTransaction], &unavailable));
+    assert!(!policy.should_retry(
+        &[RpcRequest::GetSlot],
+        &SolProbeError::InvalidResponse(String::new())
+    ));
+
+    let start = Duration::from_secs(100);
+    let mut bucket = TokenBucket::new(RateLimit::new(2.0, 2));
+    assert_eq!(bucket.reserve(start), Duration::default());
+    assert_eq!(bucket.reserve(start), Duration::default());
+    assert_eq!(bucket.reserve(start), Duration::from_millis(500));
+    assert_eq!(
+        bucket.reserve(start + Duration::from_millis(500)),
+        Duration::from_millis(500)
+    );
+
+    use crate::{MockRpcServer, RpcClient};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        sleeps: Rc<RefCell<Vec<Duration>>>,
+    }
+
+    impl Timer for Recorder {
+        fn sleep(&self, duration: Duration) -> SleepFuture<'_> {
+            self.sleeps.borrow_mut().push(duration);
+
+            Box::pin(async {})
+        }
+
+        fn now(&self) -> Duration {
+            self.sleeps.borrow().iter().sum()
+        }
+    }
+
+    // Throttles the first request and fails the second one
+    let mut server = MockRpcServer::new();
+    server.result("getSlot", serde_json::json!([]), serde_json::json!(42));
+    server
+        .fail(SolProbeError::Http {
+            status: 429,
+            retry_after: Some(1),
+        })
+        .fail(SolProbeError::Http {
+            status: 503,
+            retry_after: None,
+        });
+
+    let timer = Recorder::default();
+    let mut client = RpcClient::new(crate::SOLANA_DEVNET_URL, server);
+    client.retry(policy.clone());
+    assert_eq!(
+        smol::block_on(client.get_slot()),
+        Err(SolProbeError::MissingTimer)
+    );
+    client.timer(timer.clone());
+
+    assert_eq!(smol::block_on(client.get_slot()), Ok(42));
+    assert_eq!(
+        *timer.sleeps.borrow(),
+        [Duration::from_secs(1), Duration::from_millis(500)]
+    );
+    assert_eq!(client.transport().requests().len(), 3);
+}
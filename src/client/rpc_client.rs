@@ -0,0 +1,316 @@
+use crate::{
+    next_random, Cluster, EncodedConfirmedBlock, GetBlockConfig, GetGenesisHash, RateLimit,
+    RetryPolicy, RpcBatch, RpcMethod, RpcRequest, RpcResponse, Slot, SolProbeError, SolProbeResult,
+    Timer, TokenBucket, Transport, UiTransactionEncoding,
+};
+use core::sync::atomic::{AtomicU64, Ordering};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// What `RpcClient` does when asked to call a method that current nodes no longer serve,
+/// like a compiler lint level
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RemovedMethodLint {
+    /// Sends the request anyway
+    #[default]
+    Allow,
+    /// Sends the request and records the method, see `RpcClient::removed_method_warnings`
+    Warn,
+    /// Fails with `SolProbeError::RemovedMethod` without sending the request
+    Deny,
+}
+
+/// A JSON-RPC 2.0 client for a Solana node that sends its requests through a `Transport`
+pub struct RpcClient<T: Transport> {
+    url: String,
+    transport: T,
+    next_id: AtomicU64,
+    removed_methods: RemovedMethodLint,
+    warnings: Mutex<Vec<RpcRequest>>,
+    retry: Option<RetryPolicy>,
+    rate_limit: Option<Mutex<TokenBucket>>,
+    timer: Option<Box<dyn Timer>>,
+    /// The state of the jitter of retry delays, seeded from the timer
+    random: AtomicU64,
+}
+
+impl<T: Transport> RpcClient<T> {
+    /// A client for the node at `url`, for example `Cluster::Mainnet.url()`
+    pub fn new<U: Into<String>>(url: U, transport: T) -> Self {
+        Self {
+            url: url.into(),
+            transport,
+            next_id: AtomicU64::new(1),
+            removed_methods: RemovedMethodLint::default(),
+            warnings: Mutex::default(),
+            retry: None,
+            rate_limit: None,
+            timer: None,
+            random: AtomicU64::default(),
+        }
+    }
+
+    pub fn removed_methods(&mut self, lint: RemovedMethodLint) -> &mut Self {
+        self.removed_methods = lint;
+
+        self
+    }
+
+    /// Sends failed requests again according to `policy`, requests are sent once by default.
+    /// Needs a `timer` to wait between attempts.
+    pub fn retry(&mut self, policy: RetryPolicy) -> &mut Self {
+        self.retry = Some(policy);
+
+        self
+    }
+
+    /// Spreads requests out so that they stay within `limit`. Needs a `timer` to measure and
+    /// wait with.
+    pub fn rate_limit(&mut self, limit: RateLimit) -> &mut Self {
+        self.rate_limit = Some(Mutex::new(TokenBucket::new(limit)));
+
+        self
+    }
+
+    /// Waits for retry delays and the rate limit. Requests of a client with a retry policy or
+    /// a rate limit but no timer fail with `SolProbeError::MissingTimer`, the client itself
+    /// never reads the clock.
+    pub fn timer<S: Timer + 'static>(&mut self, timer: S) -> &mut Self {
+        self.random = AtomicU64::new(timer.now().as_nanos() as u64);
+        self.timer = Some(Box::new(timer));
+
+        self
+    }
+
+    /// The removed methods called while the lint is `Warn`, each once in the order they were
+    /// first called
+    pub fn removed_method_warnings(&self) -> Vec<RpcRequest> {
+        self.warnings
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Calls `request` with `params`, which must serialize to a JSON array, and deserializes
+    /// the `result` of the response into `R`
+    pub async fn send<P: Serialize, R: DeserializeOwned>(
+        &self,
+        request: RpcRequest,
+        params: P,
+    ) -> SolProbeResult<R> {
+        self.check_method(request)?;
+
+        let id = self.next_id();
+        let params = serde_json::to_value(params).map_err(|_| SolProbeError::InvalidRequest)?;
+        let body = request.build_request_json(id, params).to_string();
+
+        self.execute(&[request], body, RpcClient::<T>::parse_result)
+            .await
+    }
+
+    /// Calls the method `M` with typed params and result, for example
+    /// `client.call::<GetBalance>((pubkey, None))`
+    pub async fn call<M: RpcMethod>(&self, params: M::Params) -> SolProbeResult<M::Result> {
+        self.send(M::REQUEST, params).await
+    }
+
+    /// Fetches the block at `slot` with its transactions JSON encoded. Versioned
+    /// transactions are requested too, pass the block to `ConfirmedBlock::decode`.
+    pub async fn get_block(&self, slot: Slot) -> SolProbeResult<EncodedConfirmedBlock> {
+        let mut config = GetBlockConfig::new();
+        config
+            .encoding(UiTransactionEncoding::Json)
+            .max_supported_transaction_version(0);
+
+        self.get_block_with_config(slot, &config).await
+    }
+
+    pub async fn get_block_with_config(
+        &self,
+        slot: Slot,
+        config: &GetBlockConfig,
+    ) -> SolProbeResult<EncodedConfirmedBlock> {
+        self.send(RpcRequest::GetBlock, config.to_params(slot))
+            .await
+    }
+
+    pub async fn get_slot(&self) -> SolProbeResult<Slot> {
+        self.send(RpcRequest::GetSlot, ()).await
+    }
+
+    pub async fn get_block_height(&self) -> SolProbeResult<u64> {
+        self.send(RpcRequest::GetBlockHeight, ()).await
+    }
+
+    /// Fails with `SolProbeError::WrongCluster` when the node's genesis hash is not the one
+    /// of `cluster`. Clusters without a known genesis hash always pass.
+    pub async fn verify_cluster(&self, cluster: &Cluster) -> SolProbeResult<()> {
+        let expected = match cluster.genesis_hash() {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+
+        let genesis_hash = self.call::<GetGenesisHash>(()).await?;
+        if genesis_hash == expected {
+            Ok(())
+        } else {
+            Err(SolProbeError::WrongCluster(genesis_hash))
+        }
+    }
+
+    /// Starts a batch of requests sent in one round trip by `RpcBatch::send`
+    pub fn batch(&self) -> RpcBatch<'_, T> {
+        RpcBatch::new(self)
+    }
+
+    /// Posts `body`, holding `requests`, within the rate limit and sends it again while
+    /// `parse` fails with an error the retry policy allows retrying
+    pub(crate) async fn execute<R, F: Fn(&str) -> SolProbeResult<R>>(
+        &self,
+        requests: &[RpcRequest],
+        body: String,
+        parse: F,
+    ) -> SolProbeResult<R> {
+        let timer = match &self.timer {
+            Some(timer) => Some(timer),
+            None if self.retry.is_some() || self.rate_limit.is_some() => {
+                return Err(SolProbeError::MissingTimer)
+            }
+            None => None,
+        };
+        let idempotent = requests.iter().all(|request| match &self.retry {
+            Some(policy) => policy.is_idempotent(*request),
+            None => request.is_idempotent(),
+        });
+        let mut attempt = 0;
+
+        loop {
+            if let (Some(rate_limit), Some(timer)) = (&self.rate_limit, timer) {
+                let wait = rate_limit
+                    .lock()
+                    .unwrap_or_else(|error| error.into_inner())
+                    .reserve(timer.now());
+                self.sleep(wait).await;
+            }
+
+            let result = match self
+                .transport
+                .post_request(&self.url, body.clone(), idempotent)
+                .await
+            {
+                Ok(response) => parse(&response),
+                Err(error) => Err(error),
+            };
+
+            match (&self.retry, result) {
+                (Some(policy), Err(error))
+                    if attempt + 1 < policy.max_attempts
+                        && policy.should_retry(requests, &error) =>
+                {
+                    let random = next_random(&self.random);
+                    self.sleep(policy.delay(attempt, &error, random)).await;
+                    attempt += 1;
+                }
+                (_, result) => return result,
+            }
+        }
+    }
+
+    async fn sleep(&self, duration: core::time::Duration) {
+        if let (Some(timer), false) = (&self.timer, duration.as_nanos() == 0) {
+            timer.sleep(duration).await;
+        }
+    }
+
+    pub(crate) fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub(crate) fn check_method(&self, request: RpcRequest) -> SolProbeResult<()> {
+        if !request.is_removed() {
+            return Ok(());
+        }
+
+        match self.removed_methods {
+            RemovedMethodLint::Allow => Ok(()),
+            RemovedMethodLint::Deny => Err(SolProbeError::RemovedMethod(request.to_string())),
+            RemovedMethodLint::Warn => {
+                let mut warnings = self
+                    .warnings
+                    .lock()
+                    .unwrap_or_else(|error| error.into_inner());
+                if !warnings.contains(&request) {
+                    warnings.push(request);
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn parse_result<R: DeserializeOwned>(response: &str) -> SolProbeResult<R> {
+        serde_json::from_str::<RpcResponse<R>>(response)
+            .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))?
+            .into_result()
+    }
+}
+
+#[test]
+fn rpc_client_sends_json_rpc_envelopes() {
+    use crate::MockRpcServer;
+    use serde_json::json;
+
+    let mut server = MockRpcServer::new();
+    server
+        .result("getSlot", json!([]), json!(310_000_000))
+        .result("getFees", json!([]), json!(310_000_000));
+    let mut client = RpcClient::new(crate::SOLANA_DEVNET_URL, server);
+    client.removed_methods(RemovedMethodLint::Deny);
+
+    smol::block_on(async {
+        assert_eq!(client.get_slot().await, Ok(310_000_000));
+        match client.send::<_, String>(RpcRequest::GetSlot, ()).await {
+            Err(SolProbeError::InvalidResponse(reason)) => {
+                assert!(reason.starts_with("invalid type: integer"), "{}", reason)
+            }
+            other => panic!("expected an invalid response, got {:?}", other),
+        }
+        assert_eq!(
+            client
+                .send::<_, u64>(RpcRequest::Custom { method: "getFees" }, ())
+                .await,
+            Err(SolProbeError::RemovedMethod("getFees".to_string()))
+        );
+    });
+
+    let requests = client.transport().requests();
+    assert_eq!(
+        requests[0],
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "getSlot", "params": [] })
+    );
+    assert_eq!(requests[1]["id"], 2);
+    assert_eq!(requests.len(), 2);
+
+    // Warn sends the request and records the method instead
+    client.removed_methods(RemovedMethodLint::Warn);
+    smol::block_on(async {
+        for _ in 0..2 {
+            let fees = RpcRequest::Custom { method: "getFees" };
+            assert_eq!(client.send::<_, u64>(fees, ()).await, Ok(310_000_000));
+        }
+    });
+    assert_eq!(client.transport().requests().len(), 4);
+    assert_eq!(
+        client.removed_method_warnings(),
+        vec![RpcRequest::Custom { method: "getFees" }]
+    );
+}
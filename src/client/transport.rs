@@ -0,0 +1,79 @@
+use crate::SolProbeResult;
+use core::future::Future;
+use core::pin::Pin;
+
+/// The future returned by `Transport::post`. It is not `Send` so that transports built on
+/// single threaded runtimes, like the browser's `fetch`, can implement the trait.
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = SolProbeResult<String>> + 'a>>;
+
+/// Delivers an RPC request to a node. The crate does not ship an HTTP client, implement this
+/// over `surf`, `reqwest`, `fetch` or a test double and hand it to `RpcClient::new`.
+pub trait Transport {
+    /// POSTs `body` to `url` with `Content-Type: application/json` and returns the response
+    /// body. Failures to reach the node are reported as `SolProbeError::Transport` and HTTP
+    /// error statuses as `SolProbeError::Http`, which `RetryPolicy` relies on.
+    fn post<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a>;
+
+    /// `post`, knowing whether every request of `body` may be sent twice. `RpcClient` sends
+    /// through this method so that transports like `EndpointPool` can fail over safely.
+    fn post_request<'a>(
+        &'a self,
+        url: &'a str,
+        body: String,
+        _idempotent: bool,
+    ) -> TransportFuture<'a> {
+        self.post(url, body)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn post<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        (**self).post(url, body)
+    }
+
+    fn post_request<'a>(
+        &'a self,
+        url: &'a str,
+        body: String,
+        idempotent: bool,
+    ) -> TransportFuture<'a> {
+        (**self).post_request(url, body, idempotent)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn post<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        (**self).post(url, body)
+    }
+
+    fn post_request<'a>(
+        &'a self,
+        url: &'a str,
+        body: String,
+        idempotent: bool,
+    ) -> TransportFuture<'a> {
+        (**self).post_request(url, body, idempotent)
+    }
+}
+
+/// The future returned by the methods of `WsConnector` and `WsConnection`, not `Send` for the
+/// same reason as `TransportFuture`
+pub type WsFuture<'a, T> = Pin<Box<dyn Future<Output = SolProbeResult<T>> + 'a>>;
+
+/// Opens WebSockets to a node's pubsub endpoint for `PubsubClient`. Implement it over
+/// `async-tungstenite`, the browser's `WebSocket` or a mock server.
+pub trait WsConnector {
+    type Connection: WsConnection;
+
+    /// Failures to reach the node are reported as `SolProbeError::Transport`
+    fn connect<'a>(&'a self, url: &'a str) -> WsFuture<'a, Self::Connection>;
+}
+
+/// An open WebSocket exchanging JSON-RPC messages as text frames
+pub trait WsConnection {
+    fn send(&mut self, text: String) -> WsFuture<'_, ()>;
+
+    /// The next text frame, `None` once the connection is closed. Ping, pong and binary
+    /// frames are the implementation's business.
+    fn recv(&mut self) -> WsFuture<'_, Option<String>>;
+}
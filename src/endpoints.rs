@@ -1,3 +1,239 @@
+use crate::{SolProbeError, SolProbeResult};
+use core::fmt;
+use core::str::FromStr;
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 pub const SOLANA_DEVNET_URL: &str = "https://api.devnet.solana.com";
 pub const SOLANA_MAINNET_BETA_URL: &str = "https://api.mainnet-beta.solana.com";
 pub const SOLANA_TESTNET_URL: &str = "https://api.testnet.solana.com";
+/// The RPC endpoint of `solana-test-validator`
+pub const SOLANA_LOCALNET_URL: &str = "http://127.0.0.1:8899";
+
+/// A Solana cluster and its public endpoints. It parses from the monikers of the Solana CLI,
+/// `m`, `d`, `t` and `l`, or from an HTTP URL. Known clusters serialize back to the same string
+/// and custom ones to `{ "http": ..., "ws": ... }`, both of which deserialize, so it can be read
+/// from configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+    /// A `solana-test-validator` on this machine
+    Localnet,
+    Custom {
+        http: String,
+        ws: String,
+    },
+}
+
+impl Cluster {
+    /// A custom cluster whose WebSocket URL is derived from `http`, see `Cluster::from_url`
+    pub fn custom<U: Into<String>>(http: U) -> SolProbeResult<Self> {
+        let http = http.into();
+        let ws = ws_url(&http)?;
+
+        Ok(Cluster::Custom { http, ws })
+    }
+
+    /// The cluster behind a known endpoint, a custom cluster otherwise. The WebSocket URL of
+    /// a custom cluster swaps `http` for `ws` and, like the validator, listens one port above
+    /// an explicit RPC port.
+    pub fn from_url(url: &str) -> SolProbeResult<Self> {
+        let trimmed = url.trim_end_matches('/');
+
+        [
+            Cluster::Mainnet,
+            Cluster::Devnet,
+            Cluster::Testnet,
+            Cluster::Localnet,
+        ]
+        .iter()
+        .find(|cluster| cluster.url() == trimmed)
+        .cloned()
+        .map_or_else(|| Cluster::custom(url), Ok)
+    }
+
+    /// The known cluster whose genesis block has `hash`
+    pub fn from_genesis_hash(hash: &crate::Hash) -> Option<Self> {
+        [Cluster::Mainnet, Cluster::Devnet, Cluster::Testnet]
+            .iter()
+            .find(|cluster| cluster.genesis_hash().as_ref() == Some(hash))
+            .cloned()
+    }
+
+    /// The JSON-RPC endpoint, to pass to `RpcClient::new`
+    pub fn url(&self) -> &str {
+        match self {
+            Cluster::Mainnet => SOLANA_MAINNET_BETA_URL,
+            Cluster::Devnet => SOLANA_DEVNET_URL,
+            Cluster::Testnet => SOLANA_TESTNET_URL,
+            Cluster::Localnet => SOLANA_LOCALNET_URL,
+            Cluster::Custom { http, .. } => http,
+        }
+    }
+
+    /// The pubsub endpoint, to pass to `PubsubClient::new`
+    pub fn ws_url(&self) -> &str {
+        match self {
+            Cluster::Mainnet => "wss://api.mainnet-beta.solana.com",
+            Cluster::Devnet => "wss://api.devnet.solana.com",
+            Cluster::Testnet => "wss://api.testnet.solana.com",
+            Cluster::Localnet => "ws://127.0.0.1:8900",
+            Cluster::Custom { ws, .. } => ws,
+        }
+    }
+
+    /// The hash `getGenesisHash` returns on this cluster, `None` for clusters that are
+    /// created anew like `Localnet`
+    pub fn genesis_hash(&self) -> Option<crate::Hash> {
+        let hash = match self {
+            Cluster::Mainnet => "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+            Cluster::Devnet => "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG",
+            Cluster::Testnet => "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY",
+            Cluster::Localnet | Cluster::Custom { .. } => return None,
+        };
+
+        hash.parse().ok()
+    }
+}
+
+/// `ws://` or `wss://` for `http://` or `https://`, with the port after an explicit one
+fn ws_url(http: &str) -> SolProbeResult<String> {
+    let invalid = || SolProbeError::UnknownCluster(http.to_string());
+
+    let (scheme, rest) = if let Some(rest) = http.strip_prefix("https://") {
+        ("wss://", rest)
+    } else if let Some(rest) = http.strip_prefix("http://") {
+        ("ws://", rest)
+    } else {
+        return Err(invalid());
+    };
+
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let authority = match authority.rfind(':') {
+        // A colon inside brackets belongs to an IPv6 address, not to a port
+        Some(colon) if !authority[colon..].contains(']') => {
+            let port: u16 = authority[colon + 1..].parse().map_err(|_| invalid())?;
+            let port = port.checked_add(1).ok_or_else(invalid)?;
+
+            format!("{}:{}", &authority[..colon], port)
+        }
+        _ => authority.to_string(),
+    };
+    if authority.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(format!("{}{}{}", scheme, authority, path))
+}
+
+impl FromStr for Cluster {
+    type Err = SolProbeError;
+
+    fn from_str(cluster: &str) -> SolProbeResult<Self> {
+        match cluster {
+            "m" | "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "d" | "devnet" => Ok(Cluster::Devnet),
+            "t" | "testnet" => Ok(Cluster::Testnet),
+            "l" | "localnet" | "localhost" => Ok(Cluster::Localnet),
+            url => Cluster::from_url(url),
+        }
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cluster::Mainnet => write!(f, "mainnet-beta"),
+            Cluster::Devnet => write!(f, "devnet"),
+            Cluster::Testnet => write!(f, "testnet"),
+            Cluster::Localnet => write!(f, "localnet"),
+            Cluster::Custom { http, .. } => write!(f, "{}", http),
+        }
+    }
+}
+
+impl Serialize for Cluster {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Cluster::Custom { http, ws } => {
+                let mut custom = serializer.serialize_struct("Cluster", 2)?;
+                custom.serialize_field("http", http)?;
+                custom.serialize_field("ws", ws)?;
+                custom.end()
+            }
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+/// The forms a `Cluster` deserializes from
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ClusterConfig {
+    Name(String),
+    Custom { http: String, ws: String },
+}
+
+impl<'de> Deserialize<'de> for Cluster {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match ClusterConfig::deserialize(deserializer)? {
+            ClusterConfig::Name(value) => value.parse().map_err(D::Error::custom),
+            ClusterConfig::Custom { http, ws } => Ok(Cluster::Custom { http, ws }),
+        }
+    }
+}
+
+#[test]
+fn cluster_parses_monikers_and_urls() {
+    assert_eq!("m".parse(), Ok(Cluster::Mainnet));
+    assert_eq!("devnet".parse(), Ok(Cluster::Devnet));
+    assert_eq!(
+        "https://api.testnet.solana.com/".parse(),
+        Ok(Cluster::Testnet)
+    );
+    assert_eq!(
+        "http://10.0.0.7:8899/rpc".parse(),
+        Ok(Cluster::Custom {
+            http: "http://10.0.0.7:8899/rpc".to_string(),
+            ws: "ws://10.0.0.7:8900/rpc".to_string(),
+        })
+    );
+    assert_eq!(
+        Cluster::custom("https://rpc.example.com").unwrap().ws_url(),
+        "wss://rpc.example.com"
+    );
+    assert_eq!(
+        "mainnet-bet".parse::<Cluster>(),
+        Err(SolProbeError::UnknownCluster("mainnet-bet".to_string()))
+    );
+
+    let devnet_hash = Cluster::Devnet.genesis_hash().unwrap();
+    assert_eq!(
+        Cluster::from_genesis_hash(&devnet_hash),
+        Some(Cluster::Devnet)
+    );
+    assert_eq!(
+        serde_json::from_str::<Cluster>(&serde_json::to_string(&Cluster::Localnet).unwrap())
+            .unwrap(),
+        Cluster::Localnet
+    );
+
+    // A custom cluster keeps a WebSocket URL that was not derived from its HTTP one
+    let custom = Cluster::Custom {
+        http: "https://rpc.example.com".to_string(),
+        ws: "wss://pubsub.example.com".to_string(),
+    };
+    let json = serde_json::to_value(&custom).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "http": "https://rpc.example.com", "ws": "wss://pubsub.example.com" })
+    );
+    assert_eq!(serde_json::from_value::<Cluster>(json).unwrap(), custom);
+    assert_eq!(
+        serde_json::from_str::<Cluster>(r#""https://rpc.example.com""#).unwrap(),
+        Cluster::custom("https://rpc.example.com").unwrap()
+    );
+}
@@ -1,36 +1,145 @@
 mod simplified_block;
 pub use simplified_block::*;
 mod solana_blocks;
-pub use solana_blocks::TransactionError;
+pub use solana_blocks::{
+    BlockResponse, EncodedConfirmedBlock, EncodedTransactionWithStatusMeta, Legacy,
+    TransactionError, TransactionVersion, UiTransactionEncoding,
+};
 mod endpoints;
 pub use endpoints::*;
+mod programs;
+pub use programs::*;
+mod client;
+pub use client::*;
+#[cfg(any(test, feature = "test-support"))]
+mod test_support;
+#[cfg(any(test, feature = "test-support"))]
+pub use test_support::*;
 
-#[test]
-fn decode() {
-    use crate::solana_blocks::BlockResponse;
-    use json::{array, object};
-    smol::block_on(async {
-        let request_data = object! {
-            jsonrpc: "2.0",
-            id: 1,
-            method: RpcRequest::GetBlock.to_camel_case(),
-            params: array![1]
-        };
-
-        let mut request = surf::post(SOLANA_MAINNET_BETA_URL)
-            .header("Content-Type", "application/json")
-            .body_string(request_data.to_string())
+/// The latest slot and the block 100 slots before it in every encoding, the requests the
+/// fixtures answer
+#[cfg(test)]
+async fn fetch_blocks<T: Transport>(client: &RpcClient<T>) -> Vec<ConfirmedBlock> {
+    let slot = client.get_slot().await.unwrap();
+
+    let mut decoded = Vec::new();
+    for encoding in [
+        UiTransactionEncoding::Json,
+        UiTransactionEncoding::JsonParsed,
+        UiTransactionEncoding::Base64,
+    ] {
+        let mut config = GetBlockConfig::new();
+        config
+            .encoding(encoding)
+            .max_supported_transaction_version(0);
+        let block = client
+            .get_block_with_config(slot - 100, &config)
             .await
             .unwrap();
 
-        let response = request.body_string().await.unwrap();
+        let mut end = ConfirmedBlock::new();
+        end.decode(&block).unwrap();
+        decoded.push(end);
+    }
 
-        let jd = &mut serde_json::Deserializer::from_str(&response);
+    decoded
+}
 
-        let parsed_response: BlockResponse = serde_path_to_error::deserialize(jd).unwrap();
-        let mut end = ConfirmedBlock::new();
-        end.decode(&parsed_response.result);
+#[test]
+fn decode() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+    let client = RpcClient::new(
+        Cluster::Devnet.url(),
+        MockRpcServer::load(fixtures).unwrap(),
+    );
+    let decoded = smol::block_on(fetch_blocks(&client));
+
+    // The fixtures are saved under the names `FixtureRecorder` gives them
+    for entry in std::fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        let fixture: Fixture = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(
+            path.file_name().unwrap().to_str(),
+            Some(&*fixture.file_name())
+        );
+    }
+
+    // Every encoding of the block decodes to the same transactions
+    let json = &decoded[0];
+    assert!(!json.transactions.is_empty());
+    for block in &decoded {
+        assert_eq!(block.blockhash, json.blockhash);
+        assert_eq!(block.signatures, json.signatures);
+        assert_eq!(block.rewards, json.rewards);
+        assert_eq!(block.transactions.len(), json.transactions.len());
+
+        for (transaction, expected) in block.transactions.iter().zip(&json.transactions) {
+            assert_eq!(transaction.signatures, expected.signatures);
+            assert_eq!(transaction.message.version, expected.message.version);
+            assert_eq!(
+                transaction.message.resolved_account_keys(),
+                expected.message.resolved_account_keys()
+            );
+
+            // The header rebuilt from jsonParsed accounts may mark fewer accounts writable
+            let changes = |transaction: &Transaction| {
+                transaction
+                    .balance_changes()
+                    .unwrap()
+                    .into_iter()
+                    .map(|change| (change.pubkey, change.change, change.fee))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(changes(transaction), changes(expected));
+        }
+    }
+}
+
+#[test]
+#[ignore = "records the fixtures from devnet, needs network access"]
+fn record_fixtures() {
+    use std::fs;
+    use std::path::Path;
+
+    struct SurfTransport;
+
+    impl Transport for SurfTransport {
+        fn post<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+            Box::pin(async move {
+                surf::post(url)
+                    .header("Content-Type", "application/json")
+                    .body_string(body)
+                    .recv_string()
+                    .await
+                    .map_err(|error| SolProbeError::Transport(error.to_string()))
+            })
+        }
+    }
+
+    // Recorded aside first so that a failed recording leaves the fixtures as they were
+    let recorded = std::env::temp_dir().join("sol-probe-fixtures");
+    let _ = fs::remove_dir_all(&recorded);
+    let client = RpcClient::new(
+        Cluster::Devnet.url(),
+        FixtureRecorder::new(SurfTransport, &recorded),
+    );
+    smol::block_on(fetch_blocks(&client));
 
-        println!("{:#?}", &end);
-    })
+    // The blocks of the previous recording would be left over under other names
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+    let json_files = |dir: &Path| {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension().and_then(|extension| extension.to_str()) == Some("json")
+            })
+            .collect::<Vec<_>>()
+    };
+    for path in json_files(Path::new(fixtures)) {
+        fs::remove_file(path).unwrap();
+    }
+    for path in json_files(&recorded) {
+        fs::copy(&path, Path::new(fixtures).join(path.file_name().unwrap())).unwrap();
+    }
 }
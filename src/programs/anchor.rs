@@ -0,0 +1,767 @@
+use crate::programs::InstructionData;
+use crate::{Instruction, Metadata, ProgramDecoder, SolProbeError, SolProbeResult, Transaction};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Prefix of the self-invoked instruction Anchor's `emit_cpi!` uses to record an event
+pub const ANCHOR_EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// How deeply defined types may nest, a self-referencing type would recurse forever otherwise
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// The most elements decoded for a vector or array of a type that takes no bytes
+const MAX_ZERO_SIZED_ELEMENTS: usize = 1024;
+
+/// A decoded Anchor instruction, `args` is an object keyed by the IDL argument names
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorInstruction {
+    pub name: String,
+    pub accounts: Vec<AnchorAccount>,
+    pub args: Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorAccount {
+    /// The account name from the IDL, `None` for remaining accounts not listed in the IDL
+    pub name: Option<String>,
+    pub pubkey: String,
+}
+
+/// A decoded Anchor event, `fields` is an object keyed by the IDL field names
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorEvent {
+    pub name: String,
+    pub fields: Value,
+}
+
+/// What an `AnchorDecoder` attaches to an instruction. Events recorded with `emit_cpi!`
+/// show up as inner instructions of the program invoking itself.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnchorDecoded {
+    Instruction(AnchorInstruction),
+    Event(AnchorEvent),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    F32,
+    F64,
+    Bytes,
+    String,
+    Pubkey,
+    Vec(Box<IdlType>),
+    Option(Box<IdlType>),
+    COption(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum IdlFields {
+    Named(Vec<(String, IdlType)>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum IdlTypeDef {
+    Struct(IdlFields),
+    Enum(Vec<(String, IdlFields)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct IdlInstruction {
+    name: String,
+    discriminator: Vec<u8>,
+    accounts: Vec<String>,
+    args: Vec<(String, IdlType)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct IdlEvent {
+    name: String,
+    discriminator: Vec<u8>,
+    fields: IdlFields,
+}
+
+/// Decodes the instructions and events of an Anchor program from its IDL. Both the legacy
+/// IDL format and the format introduced with Anchor 0.30 are understood.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnchorDecoder {
+    program_id: String,
+    name: String,
+    instructions: Vec<IdlInstruction>,
+    events: Vec<IdlEvent>,
+    types: HashMap<String, IdlTypeDef>,
+    /// The fewest bytes each of `types` can be encoded in
+    min_sizes: HashMap<String, usize>,
+}
+
+impl AnchorDecoder {
+    pub fn new(program_id: &str, idl_json: &str) -> SolProbeResult<Self> {
+        let idl: Value = serde_json::from_str(idl_json).map_err(|_| SolProbeError::InvalidIdl)?;
+
+        let name = idl
+            .get("name")
+            .or_else(|| idl.pointer("/metadata/name"))
+            .and_then(Value::as_str)
+            .unwrap_or("anchor")
+            .to_string();
+
+        let mut types = HashMap::default();
+        for type_def in array(&idl, "types") {
+            types.insert(str_field(type_def, "name")?, parse_type_def(type_def)?);
+        }
+
+        let mut instructions = Vec::default();
+        for instruction in array(&idl, "instructions") {
+            let name = str_field(instruction, "name")?;
+            let mut accounts = Vec::default();
+            flatten_accounts(array(instruction, "accounts"), &mut accounts)?;
+
+            instructions.push(IdlInstruction {
+                discriminator: discriminator(instruction, "global", &snake_case(&name))?,
+                name,
+                accounts,
+                args: parse_named_fields(array(instruction, "args"))?,
+            });
+        }
+
+        let mut events = Vec::default();
+        for event in array(&idl, "events") {
+            let name = str_field(event, "name")?;
+            // Since 0.30 the event fields live in a type of the same name
+            let fields = match event.get("fields") {
+                Some(fields) => IdlFields::Named(parse_named_fields(
+                    fields.as_array().ok_or(SolProbeError::InvalidIdl)?,
+                )?),
+                None => match types.get(&name) {
+                    Some(IdlTypeDef::Struct(fields)) => fields.clone(),
+                    _ => return Err(SolProbeError::InvalidIdl),
+                },
+            };
+
+            events.push(IdlEvent {
+                discriminator: discriminator(event, "event", &name)?,
+                name,
+                fields,
+            });
+        }
+
+        Ok(Self {
+            program_id: program_id.to_string(),
+            name,
+            instructions,
+            events,
+            min_sizes: min_sizes(&types),
+            types,
+        })
+    }
+
+    pub fn decode_instruction(&self, instruction: &Instruction) -> SolProbeResult<AnchorDecoded> {
+        let bytes = instruction.data_bytes()?;
+
+        if let Some(event) = bytes.strip_prefix(&ANCHOR_EVENT_IX_TAG[..]) {
+            return Ok(AnchorDecoded::Event(self.decode_event(event)?));
+        }
+
+        let idl_instruction = self
+            .instructions
+            .iter()
+            .find(|idl_instruction| bytes.starts_with(&idl_instruction.discriminator))
+            .ok_or(SolProbeError::InvalidInstructionData)?;
+
+        let mut data = InstructionData::new(&bytes[idl_instruction.discriminator.len()..]);
+        let mut args = Map::default();
+        for (name, ty) in idl_instruction.args.iter() {
+            args.insert(name.clone(), self.decode_type(ty, &mut data, 0)?);
+        }
+
+        let accounts = instruction
+            .accounts
+            .iter()
+            .enumerate()
+            .map(|(index, pubkey)| AnchorAccount {
+                name: idl_instruction.accounts.get(index).cloned(),
+                pubkey: pubkey.to_string(),
+            })
+            .collect();
+
+        Ok(AnchorDecoded::Instruction(AnchorInstruction {
+            name: idl_instruction.name.clone(),
+            accounts,
+            args: Value::Object(args),
+        }))
+    }
+
+    /// Decodes the bytes of an event, starting with its 8 byte discriminator
+    pub fn decode_event(&self, bytes: &[u8]) -> SolProbeResult<AnchorEvent> {
+        let event = self
+            .events
+            .iter()
+            .find(|event| bytes.starts_with(&event.discriminator))
+            .ok_or(SolProbeError::InvalidInstructionData)?;
+
+        let mut data = InstructionData::new(&bytes[event.discriminator.len()..]);
+
+        Ok(AnchorEvent {
+            name: event.name.clone(),
+            fields: self.decode_fields(&event.fields, &mut data, 0)?,
+        })
+    }
+
+    fn decode_fields(
+        &self,
+        fields: &IdlFields,
+        data: &mut InstructionData,
+        depth: usize,
+    ) -> SolProbeResult<Value> {
+        match fields {
+            IdlFields::Named(fields) => {
+                let mut object = Map::default();
+                for (name, ty) in fields.iter() {
+                    object.insert(name.clone(), self.decode_type(ty, data, depth)?);
+                }
+
+                Ok(Value::Object(object))
+            }
+            IdlFields::Tuple(fields) => fields
+                .iter()
+                .map(|ty| self.decode_type(ty, data, depth))
+                .collect::<SolProbeResult<Vec<Value>>>()
+                .map(Value::Array),
+        }
+    }
+
+    /// Borsh deserializes a value of `ty`. Integers wider than 64 bits are returned as strings
+    /// since JSON numbers cannot hold them. `depth` counts the defined types being decoded.
+    fn decode_type(
+        &self,
+        ty: &IdlType,
+        data: &mut InstructionData,
+        depth: usize,
+    ) -> SolProbeResult<Value> {
+        let value = match ty {
+            IdlType::Bool => Value::Bool(data.bool()?),
+            IdlType::U8 => Value::from(data.u8()?),
+            IdlType::I8 => Value::from(data.u8()? as i8),
+            IdlType::U16 => Value::from(data.u16()?),
+            IdlType::I16 => Value::from(data.u16()? as i16),
+            IdlType::U32 => Value::from(data.u32()?),
+            IdlType::I32 => Value::from(data.u32()? as i32),
+            IdlType::U64 => Value::from(data.u64()?),
+            IdlType::I64 => Value::from(data.u64()? as i64),
+            IdlType::U128 => Value::String(data.u128()?.to_string()),
+            IdlType::I128 => Value::String((data.u128()? as i128).to_string()),
+            IdlType::F32 => Value::from(f32::from_le_bytes(data.array()?)),
+            IdlType::F64 => Value::from(f64::from_le_bytes(data.array()?)),
+            IdlType::Bytes => {
+                let len = data.u32()? as usize;
+                Value::from(data.take(len)?.to_vec())
+            }
+            IdlType::String => Value::String(data.borsh_string()?),
+            IdlType::Pubkey => Value::String(data.pubkey()?.to_string()),
+            IdlType::Vec(inner) => {
+                let len = data.u32()? as usize;
+                self.decode_sequence(inner, len, data, depth)?
+            }
+            IdlType::Option(inner) => match data.bool()? {
+                true => self.decode_type(inner, data, depth)?,
+                false => Value::Null,
+            },
+            IdlType::COption(inner) => match data.u32()? {
+                0 => Value::Null,
+                1 => self.decode_type(inner, data, depth)?,
+                _ => return Err(SolProbeError::InvalidInstructionData),
+            },
+            IdlType::Array(inner, len) => self.decode_sequence(inner, *len, data, depth)?,
+            IdlType::Defined(_) if depth >= MAX_TYPE_DEPTH => {
+                return Err(SolProbeError::InvalidInstructionData)
+            }
+            IdlType::Defined(name) => match self.types.get(name) {
+                Some(IdlTypeDef::Struct(fields)) => self.decode_fields(fields, data, depth + 1)?,
+                Some(IdlTypeDef::Enum(variants)) => {
+                    let (variant, fields) = variants
+                        .get(data.u8()? as usize)
+                        .ok_or(SolProbeError::InvalidInstructionData)?;
+
+                    match fields {
+                        IdlFields::Named(named) if named.is_empty() => {
+                            Value::String(variant.clone())
+                        }
+                        fields => {
+                            let mut object = Map::default();
+                            object.insert(
+                                variant.clone(),
+                                self.decode_fields(fields, data, depth + 1)?,
+                            );
+
+                            Value::Object(object)
+                        }
+                    }
+                }
+                None => return Err(SolProbeError::InvalidIdl),
+            },
+        };
+
+        Ok(value)
+    }
+
+    /// Decodes `len` values of `ty`. The length comes from the data, so it is checked
+    /// against the bytes left before anything is decoded.
+    fn decode_sequence(
+        &self,
+        ty: &IdlType,
+        len: usize,
+        data: &mut InstructionData,
+        depth: usize,
+    ) -> SolProbeResult<Value> {
+        let limit = match min_size(ty, &self.min_sizes) {
+            0 => MAX_ZERO_SIZED_ELEMENTS,
+            size => data.remaining() / size,
+        };
+        if len > limit {
+            return Err(SolProbeError::InvalidInstructionData);
+        }
+
+        (0..len)
+            .map(|_| self.decode_type(ty, data, depth))
+            .collect::<SolProbeResult<Vec<Value>>>()
+            .map(Value::Array)
+    }
+}
+
+/// The fewest bytes a value of `ty` can be encoded in, given those of the defined types
+fn min_size(ty: &IdlType, min_sizes: &HashMap<String, usize>) -> usize {
+    match ty {
+        IdlType::Bool | IdlType::U8 | IdlType::I8 | IdlType::Option(_) => 1,
+        IdlType::U16 | IdlType::I16 => 2,
+        IdlType::U32 | IdlType::I32 | IdlType::F32 => 4,
+        IdlType::Bytes | IdlType::String | IdlType::Vec(_) | IdlType::COption(_) => 4,
+        IdlType::U64 | IdlType::I64 | IdlType::F64 => 8,
+        IdlType::U128 | IdlType::I128 => 16,
+        IdlType::Pubkey => 32,
+        IdlType::Array(inner, len) => min_size(inner, min_sizes).saturating_mul(*len),
+        IdlType::Defined(name) => min_sizes.get(name).copied().unwrap_or_default(),
+    }
+}
+
+/// The fewest bytes each defined type can be encoded in. The sizes grow from zero one level
+/// of nesting per round, so a type nested in itself costs `MAX_TYPE_DEPTH` rounds at most
+/// instead of a walk of every path through it.
+fn min_sizes(types: &HashMap<String, IdlTypeDef>) -> HashMap<String, usize> {
+    let mut min_sizes = types
+        .keys()
+        .map(|name| (name.clone(), 0))
+        .collect::<HashMap<String, usize>>();
+
+    for _ in 0..MAX_TYPE_DEPTH {
+        let next = types
+            .iter()
+            .map(|(name, type_def)| {
+                let size = match type_def {
+                    IdlTypeDef::Struct(IdlFields::Named(fields)) => fields
+                        .iter()
+                        .map(|(_, ty)| min_size(ty, &min_sizes))
+                        .fold(0, usize::saturating_add),
+                    IdlTypeDef::Struct(IdlFields::Tuple(fields)) => fields
+                        .iter()
+                        .map(|ty| min_size(ty, &min_sizes))
+                        .fold(0, usize::saturating_add),
+                    IdlTypeDef::Enum(_) => 1,
+                };
+
+                (name.clone(), size)
+            })
+            .collect::<HashMap<String, usize>>();
+
+        if next == min_sizes {
+            break;
+        }
+        min_sizes = next;
+    }
+
+    min_sizes
+}
+
+impl ProgramDecoder for AnchorDecoder {
+    fn program_id(&self) -> &str {
+        &self.program_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn decode(&self, instruction: &Instruction) -> SolProbeResult<Value> {
+        serde_json::to_value(self.decode_instruction(instruction)?)
+            .map_err(|_| SolProbeError::InvalidInstructionData)
+    }
+}
+
+impl Transaction {
+    /// The events emitted by the decoder's program. Events logged with `emit!` as
+    /// `Program data:` lines come first, followed by the ones recorded with `emit_cpi!`.
+    pub fn anchor_events(&self, decoder: &AnchorDecoder) -> Vec<AnchorEvent> {
+        let logs = self
+            .metadata
+            .as_ref()
+            .map(Metadata::parse_logs)
+            .unwrap_or_default();
+        let mut events: Vec<AnchorEvent> = logs
+            .flatten()
+            .into_iter()
+            .filter(|invocation| invocation.program_id == decoder.program_id)
+            .flat_map(|invocation| invocation.data.iter())
+            .filter_map(|data| {
+                data.split(' ')
+                    .next()
+                    .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+                    .and_then(|bytes| decoder.decode_event(&bytes).ok())
+            })
+            .collect();
+
+        self.message
+            .instructions
+            .iter()
+            .flat_map(|instruction| instruction.inner_instructions.iter())
+            .filter(|instruction| instruction.program_id.to_string() == decoder.program_id)
+            .filter_map(
+                |instruction| match decoder.decode_instruction(instruction) {
+                    Ok(AnchorDecoded::Event(event)) => Some(event),
+                    _ => None,
+                },
+            )
+            .for_each(|event| events.push(event));
+
+        events
+    }
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn str_field(value: &Value, key: &str) -> SolProbeResult<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or(SolProbeError::InvalidIdl)
+}
+
+/// Uses the IDL's explicit discriminator when present, otherwise derives it the way
+/// Anchor does from the first 8 bytes of `sha256("<namespace>:<name>")`
+fn discriminator(value: &Value, namespace: &str, name: &str) -> SolProbeResult<Vec<u8>> {
+    match value.get("discriminator") {
+        Some(discriminator) => {
+            serde_json::from_value(discriminator.clone()).map_err(|_| SolProbeError::InvalidIdl)
+        }
+        None => {
+            let hash = Sha256::digest(format!("{}:{}", namespace, name).as_bytes());
+
+            Ok(hash[..8].to_vec())
+        }
+    }
+}
+
+fn flatten_accounts(accounts: &[Value], names: &mut Vec<String>) -> SolProbeResult<()> {
+    for account in accounts {
+        match account.get("accounts").and_then(Value::as_array) {
+            Some(nested) => flatten_accounts(nested, names)?,
+            None => names.push(str_field(account, "name")?),
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_named_fields(fields: &[Value]) -> SolProbeResult<Vec<(String, IdlType)>> {
+    fields
+        .iter()
+        .map(|field| {
+            let ty = field.get("type").ok_or(SolProbeError::InvalidIdl)?;
+
+            Ok((str_field(field, "name")?, parse_type(ty)?))
+        })
+        .collect()
+}
+
+fn parse_fields(fields: Option<&Value>) -> SolProbeResult<IdlFields> {
+    let fields = match fields.and_then(Value::as_array) {
+        Some(fields) => fields,
+        None => return Ok(IdlFields::Named(Vec::default())),
+    };
+
+    match fields.first() {
+        Some(field) if field.get("name").is_some() => {
+            Ok(IdlFields::Named(parse_named_fields(fields)?))
+        }
+        _ => fields
+            .iter()
+            .map(parse_type)
+            .collect::<SolProbeResult<Vec<IdlType>>>()
+            .map(IdlFields::Tuple),
+    }
+}
+
+fn parse_type_def(type_def: &Value) -> SolProbeResult<IdlTypeDef> {
+    let ty = type_def.get("type").ok_or(SolProbeError::InvalidIdl)?;
+
+    match ty.get("kind").and_then(Value::as_str) {
+        Some("struct") => Ok(IdlTypeDef::Struct(parse_fields(ty.get("fields"))?)),
+        Some("enum") => array(ty, "variants")
+            .iter()
+            .map(|variant| {
+                Ok((
+                    str_field(variant, "name")?,
+                    parse_fields(variant.get("fields"))?,
+                ))
+            })
+            .collect::<SolProbeResult<Vec<(String, IdlFields)>>>()
+            .map(IdlTypeDef::Enum),
+        _ => Err(SolProbeError::InvalidIdl),
+    }
+}
+
+fn parse_type(ty: &Value) -> SolProbeResult<IdlType> {
+    if let Some(name) = ty.as_str() {
+        let ty = match name {
+            "bool" => IdlType::Bool,
+            "u8" => IdlType::U8,
+            "i8" => IdlType::I8,
+            "u16" => IdlType::U16,
+            "i16" => IdlType::I16,
+            "u32" => IdlType::U32,
+            "i32" => IdlType::I32,
+            "u64" => IdlType::U64,
+            "i64" => IdlType::I64,
+            "u128" => IdlType::U128,
+            "i128" => IdlType::I128,
+            "f32" => IdlType::F32,
+            "f64" => IdlType::F64,
+            "bytes" => IdlType::Bytes,
+            "string" => IdlType::String,
+            "publicKey" | "pubkey" => IdlType::Pubkey,
+            _ => return Err(SolProbeError::InvalidIdl),
+        };
+
+        return Ok(ty);
+    }
+
+    if let Some(inner) = ty.get("vec") {
+        Ok(IdlType::Vec(Box::new(parse_type(inner)?)))
+    } else if let Some(inner) = ty.get("option") {
+        Ok(IdlType::Option(Box::new(parse_type(inner)?)))
+    } else if let Some(inner) = ty.get("coption") {
+        Ok(IdlType::COption(Box::new(parse_type(inner)?)))
+    } else if let Some(array) = ty.get("array").and_then(Value::as_array) {
+        match (array.first(), array.get(1).and_then(Value::as_u64)) {
+            (Some(inner), Some(len)) => {
+                Ok(IdlType::Array(Box::new(parse_type(inner)?), len as usize))
+            }
+            _ => Err(SolProbeError::InvalidIdl),
+        }
+    } else if let Some(defined) = ty.get("defined") {
+        // Legacy IDLs use `{"defined": "Name"}`, 0.30 uses `{"defined": {"name": "Name"}}`
+        defined
+            .as_str()
+            .or_else(|| defined.get("name").and_then(Value::as_str))
+            .map(|name| IdlType::Defined(name.to_string()))
+            .ok_or(SolProbeError::InvalidIdl)
+    } else {
+        Err(SolProbeError::InvalidIdl)
+    }
+}
+
+/// Legacy IDLs name instructions in camelCase while the discriminator is derived from the
+/// snake_case name of the Rust function
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (index, current) in chars.iter().enumerate() {
+        if current.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+
+        snake.extend(current.to_lowercase());
+    }
+
+    snake
+}
+
+#[test]
+fn decode_anchor_instruction_and_event() {
+    let idl = r#"{
+        "version": "0.1.0",
+        "name": "counter",
+        "instructions": [
+            {
+                "name": "setCount",
+                "accounts": [
+                    { "name": "counter", "isMut": true, "isSigner": false },
+                    { "name": "authority", "isMut": false, "isSigner": true }
+                ],
+                "args": [
+                    { "name": "count", "type": "u64" },
+                    { "name": "label", "type": { "option": "string" } },
+                    { "name": "mode", "type": { "defined": "Mode" } }
+                ]
+            }
+        ],
+        "types": [
+            {
+                "name": "Mode",
+                "type": { "kind": "enum", "variants": [{ "name": "Fast" }, { "name": "Slow" }] }
+            },
+            {
+                "name": "Node",
+                "type": {
+                    "kind": "struct",
+                    "fields": [{ "name": "next", "type": { "option": { "defined": "Node" } } }]
+                }
+            },
+            {
+                "name": "Tree",
+                "type": {
+                    "kind": "struct",
+                    "fields": [{ "name": "children", "type": { "array": [{ "defined": "Tree" }, 2] } }]
+                }
+            },
+            { "name": "Unit", "type": { "kind": "struct", "fields": [] } }
+        ],
+        "events": [
+            {
+                "name": "CountChanged",
+                "fields": [{ "name": "count", "type": "u64", "index": false }]
+            }
+        ]
+    }"#;
+    let program_id = "Counter111111111111111111111111111111111111";
+    let decoder = AnchorDecoder::new(program_id, idl).unwrap();
+
+    let mut data = Sha256::digest(b"global:set_count")[..8].to_vec();
+    data.extend_from_slice(&42u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(b"gm");
+    data.push(1);
+
+    let instruction = Instruction {
+        program_id: program_id.parse().unwrap(),
+        accounts: vec![
+            "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+                .parse()
+                .unwrap(),
+            "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe"
+                .parse()
+                .unwrap(),
+        ],
+        data: bs58::encode(&data).into_string(),
+        ..Instruction::default()
+    };
+
+    match decoder.decode_instruction(&instruction).unwrap() {
+        AnchorDecoded::Instruction(decoded) => {
+            assert_eq!(decoded.name, "setCount");
+            assert_eq!(decoded.accounts[1].name.as_deref(), Some("authority"));
+            assert_eq!(
+                decoded.args,
+                serde_json::json!({ "count": 42, "label": "gm", "mode": "Slow" })
+            );
+        }
+        AnchorDecoded::Event(_) => panic!("expected an instruction"),
+    }
+
+    let mut event = Sha256::digest(b"event:CountChanged")[..8].to_vec();
+    event.extend_from_slice(&7u64.to_le_bytes());
+
+    let mut transaction = Transaction::new();
+    transaction.metadata = Some(crate::Metadata {
+        err: None,
+        fee: crate::Lamports(5000),
+        pre_balances: Vec::default(),
+        post_balances: Vec::default(),
+        log_messages: vec![
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: SetCount".to_string(),
+            format!(
+                "Program data: {}",
+                base64::engine::general_purpose::STANDARD.encode(&event)
+            ),
+            format!("Program {} success", program_id),
+        ],
+        pre_token_balances: Vec::default(),
+        post_token_balances: Vec::default(),
+        rewards: Vec::default(),
+    });
+
+    assert_eq!(
+        transaction.anchor_events(&decoder),
+        vec![AnchorEvent {
+            name: "CountChanged".to_string(),
+            fields: serde_json::json!({ "count": 7 }),
+        }]
+    );
+
+    // Lengths are checked against the data left and types may only nest so deep
+    let decode = |ty: &str, bytes: &[u8]| {
+        let ty = parse_type(&serde_json::from_str(ty).unwrap()).unwrap();
+        decoder.decode_type(&ty, &mut InstructionData::new(bytes), 0)
+    };
+    let huge = u32::MAX.to_le_bytes();
+    assert_eq!(
+        decode(r#"{ "vec": "u64" }"#, &huge),
+        Err(SolProbeError::InvalidInstructionData)
+    );
+    assert_eq!(
+        decode(r#"{ "vec": { "defined": "Unit" } }"#, &huge),
+        Err(SolProbeError::InvalidInstructionData)
+    );
+    assert_eq!(
+        decode(r#"{ "vec": { "defined": "Unit" } }"#, &2u32.to_le_bytes()),
+        Ok(serde_json::json!([[], []]))
+    );
+    assert_eq!(
+        decode(r#"{ "defined": "Node" }"#, &[1, 1, 0]),
+        Ok(serde_json::json!({ "next": { "next": { "next": null } } }))
+    );
+    assert_eq!(
+        decode(r#"{ "defined": "Node" }"#, &[1; 64]),
+        Err(SolProbeError::InvalidInstructionData)
+    );
+    assert_eq!(
+        decode(r#"{ "defined": "Tree" }"#, &[]),
+        Err(SolProbeError::InvalidInstructionData)
+    );
+}
@@ -0,0 +1,111 @@
+use crate::{Pubkey, SolProbeError, SolProbeResult};
+use core::convert::TryFrom;
+
+/// Little-endian reader over the raw bytes of an instruction's data, covering the
+/// bincode and borsh primitives used by on-chain programs
+pub(crate) struct InstructionData<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> InstructionData<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Consumes and returns every byte not read yet
+    pub(crate) fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.bytes[self.position..];
+        self.position = self.bytes.len();
+
+        rest
+    }
+
+    /// The number of bytes not read yet
+    pub(crate) fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> SolProbeResult<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .ok_or(SolProbeError::InvalidInstructionData)?;
+        let slice = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(SolProbeError::InvalidInstructionData)?;
+        self.position = end;
+
+        Ok(slice)
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> SolProbeResult<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+
+        Ok(array)
+    }
+
+    pub(crate) fn u8(&mut self) -> SolProbeResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> SolProbeResult<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SolProbeError::InvalidInstructionData),
+        }
+    }
+
+    pub(crate) fn u16(&mut self) -> SolProbeResult<u16> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> SolProbeResult<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> SolProbeResult<u64> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    pub(crate) fn u128(&mut self) -> SolProbeResult<u128> {
+        Ok(u128::from_le_bytes(self.array()?))
+    }
+
+    /// A 32 byte public key
+    pub(crate) fn pubkey(&mut self) -> SolProbeResult<Pubkey> {
+        Ok(Pubkey::new(self.array()?))
+    }
+
+    /// An optional public key prefixed by a one byte tag, as packed by the token programs
+    pub(crate) fn option_pubkey(&mut self) -> SolProbeResult<Option<Pubkey>> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.pubkey()?)),
+            _ => Err(SolProbeError::InvalidInstructionData),
+        }
+    }
+
+    /// A string prefixed by a u64 length as written by bincode
+    pub(crate) fn bincode_string(&mut self) -> SolProbeResult<String> {
+        let len =
+            usize::try_from(self.u64()?).map_err(|_| SolProbeError::InvalidInstructionData)?;
+
+        self.utf8(len)
+    }
+
+    /// A string prefixed by a u32 length as written by borsh
+    pub(crate) fn borsh_string(&mut self) -> SolProbeResult<String> {
+        let len = self.u32()? as usize;
+
+        self.utf8(len)
+    }
+
+    fn utf8(&mut self, len: usize) -> SolProbeResult<String> {
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| SolProbeError::InvalidInstructionData)
+    }
+}
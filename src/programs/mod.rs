@@ -0,0 +1,10 @@
+mod data;
+pub(crate) use data::*;
+mod system;
+pub use system::*;
+mod token;
+pub use token::*;
+mod registry;
+pub use registry::*;
+mod anchor;
+pub use anchor::*;
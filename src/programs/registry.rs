@@ -0,0 +1,175 @@
+use crate::{
+    Instruction, SolProbeResult, SystemDecoder, TokenDecoder, Transaction, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Decodes the instructions of a single on-chain program. Implement this for in-house
+/// programs and add it to a `ProgramDecoderRegistry` to have `ConfirmedBlock::decode_with`
+/// attach the output to every instruction of that program.
+pub trait ProgramDecoder: Send + Sync {
+    /// The program id this decoder is registered under
+    fn program_id(&self) -> &str;
+
+    /// A short human readable name for the program, like `system` or `spl-token`
+    fn name(&self) -> &str;
+
+    fn decode(&self, instruction: &Instruction) -> SolProbeResult<serde_json::Value>;
+}
+
+/// The output of a `ProgramDecoder` attached to an `Instruction`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedInstruction {
+    pub program: String,
+    pub instruction: serde_json::Value,
+}
+
+impl DecodedInstruction {
+    /// Converts the JSON output back into the decoder's typed instruction,
+    /// for example `decoded.to_typed::<SystemInstruction>()`
+    pub fn to_typed<T: DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_value(self.instruction.clone()).ok()
+    }
+}
+
+pub struct ProgramDecoderRegistry {
+    decoders: HashMap<String, Box<dyn ProgramDecoder>>,
+}
+
+impl Default for ProgramDecoderRegistry {
+    fn default() -> Self {
+        ProgramDecoderRegistry::new()
+    }
+}
+
+impl ProgramDecoderRegistry {
+    /// A registry with the decoders shipped with the crate
+    pub fn new() -> Self {
+        let mut registry = ProgramDecoderRegistry::empty();
+        registry
+            .register(SystemDecoder)
+            .register(TokenDecoder::new(TOKEN_PROGRAM_ID))
+            .register(TokenDecoder::new(TOKEN_2022_PROGRAM_ID));
+
+        registry
+    }
+
+    /// A registry without any decoder
+    pub fn empty() -> Self {
+        Self {
+            decoders: HashMap::default(),
+        }
+    }
+
+    /// Adds a decoder, replacing any decoder already registered for the same program id
+    pub fn register<D: ProgramDecoder + 'static>(&mut self, decoder: D) -> &mut Self {
+        self.decoders
+            .insert(decoder.program_id().to_string(), Box::new(decoder));
+
+        self
+    }
+
+    pub fn remove(&mut self, program_id: &str) -> Option<Box<dyn ProgramDecoder>> {
+        self.decoders.remove(program_id)
+    }
+
+    pub fn get(&self, program_id: &str) -> Option<&dyn ProgramDecoder> {
+        self.decoders
+            .get(program_id)
+            .map(|decoder| decoder.as_ref())
+    }
+
+    /// Runs the decoder registered for the instruction's program. Returns `None` when no decoder
+    /// is registered or when the decoder does not recognize the instruction data.
+    pub fn decode(&self, instruction: &Instruction) -> Option<DecodedInstruction> {
+        let decoder = self.get(&instruction.program_id.to_string())?;
+
+        decoder
+            .decode(instruction)
+            .ok()
+            .map(|decoded| DecodedInstruction {
+                program: decoder.name().to_string(),
+                instruction: decoded,
+            })
+    }
+}
+
+impl Transaction {
+    /// Attaches the output of the registry's decoders to every instruction and inner instruction
+    pub fn decode_programs(&mut self, registry: &ProgramDecoderRegistry) -> &mut Self {
+        for instruction in self.message.instructions.iter_mut() {
+            instruction.decoded = registry.decode(instruction);
+
+            for inner in instruction.inner_instructions.iter_mut() {
+                inner.decoded = registry.decode(inner);
+            }
+        }
+
+        self
+    }
+}
+
+#[test]
+fn registry_attaches_custom_decoder_output() {
+    use crate::{SystemInstruction, SYSTEM_PROGRAM_ID};
+
+    struct MemoDecoder;
+
+    impl ProgramDecoder for MemoDecoder {
+        fn program_id(&self) -> &str {
+            "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"
+        }
+
+        fn name(&self) -> &str {
+            "memo"
+        }
+
+        fn decode(&self, instruction: &Instruction) -> SolProbeResult<serde_json::Value> {
+            let memo = String::from_utf8(instruction.data_bytes()?)
+                .map_err(|_| crate::SolProbeError::InvalidInstructionData)?;
+
+            Ok(serde_json::Value::String(memo))
+        }
+    }
+
+    let mut registry = ProgramDecoderRegistry::new();
+    registry.register(MemoDecoder);
+
+    let memo = Instruction {
+        program_id: "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"
+            .parse()
+            .unwrap(),
+        data: bs58::encode("gm").into_string(),
+        ..Instruction::default()
+    };
+    let mut allocate = 8u32.to_le_bytes().to_vec();
+    allocate.extend_from_slice(&165u64.to_le_bytes());
+    let system = Instruction {
+        program_id: SYSTEM_PROGRAM_ID.parse().unwrap(),
+        accounts: vec!["AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+            .parse()
+            .unwrap()],
+        data: bs58::encode(allocate).into_string(),
+        ..Instruction::default()
+    };
+
+    assert!(ProgramDecoderRegistry::empty().decode(&memo).is_none());
+    let decoded = registry.decode(&memo).unwrap();
+    assert_eq!(decoded.program, "memo");
+    assert_eq!(decoded.instruction, serde_json::json!("gm"));
+    assert_eq!(
+        registry
+            .decode(&system)
+            .unwrap()
+            .to_typed::<SystemInstruction>(),
+        Some(SystemInstruction::Allocate {
+            account: "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+                .parse()
+                .unwrap(),
+            space: 165,
+        })
+    );
+}
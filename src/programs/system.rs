@@ -0,0 +1,235 @@
+use crate::programs::InstructionData;
+use crate::{Instruction, ProgramDecoder, Pubkey, SolProbeError, SolProbeResult};
+use serde::{Deserialize, Serialize};
+
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+/// An instruction of the System program with its accounts named by the role they play.
+/// The instruction data is bincode encoded with a u32 discriminant.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum SystemInstruction {
+    CreateAccount {
+        from: Pubkey,
+        to: Pubkey,
+        lamports: u64,
+        space: u64,
+        owner: Pubkey,
+    },
+    Assign {
+        account: Pubkey,
+        owner: Pubkey,
+    },
+    Transfer {
+        from: Pubkey,
+        to: Pubkey,
+        lamports: u64,
+    },
+    CreateAccountWithSeed {
+        from: Pubkey,
+        to: Pubkey,
+        base: Pubkey,
+        seed: String,
+        lamports: u64,
+        space: u64,
+        owner: Pubkey,
+    },
+    AdvanceNonceAccount {
+        nonce_account: Pubkey,
+        recent_blockhashes_sysvar: Pubkey,
+        nonce_authority: Pubkey,
+    },
+    WithdrawNonceAccount {
+        nonce_account: Pubkey,
+        to: Pubkey,
+        recent_blockhashes_sysvar: Pubkey,
+        rent_sysvar: Pubkey,
+        nonce_authority: Pubkey,
+        lamports: u64,
+    },
+    InitializeNonceAccount {
+        nonce_account: Pubkey,
+        recent_blockhashes_sysvar: Pubkey,
+        rent_sysvar: Pubkey,
+        nonce_authority: Pubkey,
+    },
+    AuthorizeNonceAccount {
+        nonce_account: Pubkey,
+        nonce_authority: Pubkey,
+        new_authority: Pubkey,
+    },
+    Allocate {
+        account: Pubkey,
+        space: u64,
+    },
+    AllocateWithSeed {
+        account: Pubkey,
+        base: Pubkey,
+        seed: String,
+        space: u64,
+        owner: Pubkey,
+    },
+    AssignWithSeed {
+        account: Pubkey,
+        base: Pubkey,
+        seed: String,
+        owner: Pubkey,
+    },
+    TransferWithSeed {
+        from: Pubkey,
+        from_base: Pubkey,
+        to: Pubkey,
+        lamports: u64,
+        from_seed: String,
+        from_owner: Pubkey,
+    },
+    UpgradeNonceAccount {
+        nonce_account: Pubkey,
+    },
+}
+
+impl SystemInstruction {
+    pub fn decode(instruction: &Instruction) -> SolProbeResult<Self> {
+        if instruction.program_id.to_string() != SYSTEM_PROGRAM_ID {
+            return Err(SolProbeError::ProgramIdMismatch);
+        }
+
+        let bytes = instruction.data_bytes()?;
+        let mut data = InstructionData::new(&bytes);
+        let account = |index: usize| instruction.account(index);
+
+        let decoded = match data.u32()? {
+            0 => SystemInstruction::CreateAccount {
+                from: account(0)?,
+                to: account(1)?,
+                lamports: data.u64()?,
+                space: data.u64()?,
+                owner: data.pubkey()?,
+            },
+            1 => SystemInstruction::Assign {
+                account: account(0)?,
+                owner: data.pubkey()?,
+            },
+            2 => SystemInstruction::Transfer {
+                from: account(0)?,
+                to: account(1)?,
+                lamports: data.u64()?,
+            },
+            3 => SystemInstruction::CreateAccountWithSeed {
+                from: account(0)?,
+                to: account(1)?,
+                base: data.pubkey()?,
+                seed: data.bincode_string()?,
+                lamports: data.u64()?,
+                space: data.u64()?,
+                owner: data.pubkey()?,
+            },
+            4 => SystemInstruction::AdvanceNonceAccount {
+                nonce_account: account(0)?,
+                recent_blockhashes_sysvar: account(1)?,
+                nonce_authority: account(2)?,
+            },
+            5 => SystemInstruction::WithdrawNonceAccount {
+                nonce_account: account(0)?,
+                to: account(1)?,
+                recent_blockhashes_sysvar: account(2)?,
+                rent_sysvar: account(3)?,
+                nonce_authority: account(4)?,
+                lamports: data.u64()?,
+            },
+            6 => SystemInstruction::InitializeNonceAccount {
+                nonce_account: account(0)?,
+                recent_blockhashes_sysvar: account(1)?,
+                rent_sysvar: account(2)?,
+                nonce_authority: data.pubkey()?,
+            },
+            7 => SystemInstruction::AuthorizeNonceAccount {
+                nonce_account: account(0)?,
+                nonce_authority: account(1)?,
+                new_authority: data.pubkey()?,
+            },
+            8 => SystemInstruction::Allocate {
+                account: account(0)?,
+                space: data.u64()?,
+            },
+            9 => SystemInstruction::AllocateWithSeed {
+                account: account(0)?,
+                base: data.pubkey()?,
+                seed: data.bincode_string()?,
+                space: data.u64()?,
+                owner: data.pubkey()?,
+            },
+            10 => SystemInstruction::AssignWithSeed {
+                account: account(0)?,
+                base: data.pubkey()?,
+                seed: data.bincode_string()?,
+                owner: data.pubkey()?,
+            },
+            11 => SystemInstruction::TransferWithSeed {
+                from: account(0)?,
+                from_base: account(1)?,
+                to: account(2)?,
+                lamports: data.u64()?,
+                from_seed: data.bincode_string()?,
+                from_owner: data.pubkey()?,
+            },
+            12 => SystemInstruction::UpgradeNonceAccount {
+                nonce_account: account(0)?,
+            },
+            _ => return Err(SolProbeError::InvalidInstructionData),
+        };
+
+        Ok(decoded)
+    }
+}
+
+/// Registers `SystemInstruction` decoding with a `ProgramDecoderRegistry`
+pub struct SystemDecoder;
+
+impl ProgramDecoder for SystemDecoder {
+    fn program_id(&self) -> &str {
+        SYSTEM_PROGRAM_ID
+    }
+
+    fn name(&self) -> &str {
+        "system"
+    }
+
+    fn decode(&self, instruction: &Instruction) -> SolProbeResult<serde_json::Value> {
+        serde_json::to_value(SystemInstruction::decode(instruction)?)
+            .map_err(|_| SolProbeError::InvalidInstructionData)
+    }
+}
+
+#[test]
+fn decode_system_transfer() {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&1_500_000_000u64.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: SYSTEM_PROGRAM_ID.parse().unwrap(),
+        accounts: vec![
+            "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe"
+                .parse()
+                .unwrap(),
+            "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+                .parse()
+                .unwrap(),
+        ],
+        data: bs58::encode(&data).into_string(),
+        ..Instruction::default()
+    };
+
+    assert_eq!(
+        SystemInstruction::decode(&instruction),
+        Ok(SystemInstruction::Transfer {
+            from: "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe"
+                .parse()
+                .unwrap(),
+            to: "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+                .parse()
+                .unwrap(),
+            lamports: 1_500_000_000,
+        })
+    );
+}
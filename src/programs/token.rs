@@ -0,0 +1,850 @@
+use crate::programs::InstructionData;
+use crate::{Instruction, ProgramDecoder, Pubkey, SolProbeError, SolProbeResult, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// An instruction of the SPL Token program or of Token-2022 with its accounts named by
+/// the role they play. Additional multisig signer accounts are not included.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum TokenInstruction {
+    InitializeMint {
+        mint: Pubkey,
+        decimals: u8,
+        mint_authority: Pubkey,
+        freeze_authority: Option<Pubkey>,
+    },
+    InitializeAccount {
+        account: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+    },
+    InitializeMultisig {
+        multisig: Pubkey,
+        signers: Vec<Pubkey>,
+        m: u8,
+    },
+    Transfer {
+        source: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+    },
+    Approve {
+        source: Pubkey,
+        delegate: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    },
+    Revoke {
+        source: Pubkey,
+        owner: Pubkey,
+    },
+    SetAuthority {
+        account: Pubkey,
+        current_authority: Pubkey,
+        authority_type: AuthorityType,
+        new_authority: Option<Pubkey>,
+    },
+    MintTo {
+        mint: Pubkey,
+        account: Pubkey,
+        mint_authority: Pubkey,
+        amount: u64,
+    },
+    Burn {
+        account: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+    },
+    CloseAccount {
+        account: Pubkey,
+        destination: Pubkey,
+        owner: Pubkey,
+    },
+    FreezeAccount {
+        account: Pubkey,
+        mint: Pubkey,
+        freeze_authority: Pubkey,
+    },
+    ThawAccount {
+        account: Pubkey,
+        mint: Pubkey,
+        freeze_authority: Pubkey,
+    },
+    TransferChecked {
+        source: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: u8,
+    },
+    ApproveChecked {
+        source: Pubkey,
+        mint: Pubkey,
+        delegate: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        decimals: u8,
+    },
+    MintToChecked {
+        mint: Pubkey,
+        account: Pubkey,
+        mint_authority: Pubkey,
+        amount: u64,
+        decimals: u8,
+    },
+    BurnChecked {
+        account: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: u8,
+    },
+    InitializeAccount2 {
+        account: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+    },
+    SyncNative {
+        account: Pubkey,
+    },
+    InitializeAccount3 {
+        account: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+    },
+    InitializeMultisig2 {
+        multisig: Pubkey,
+        signers: Vec<Pubkey>,
+        m: u8,
+    },
+    InitializeMint2 {
+        mint: Pubkey,
+        decimals: u8,
+        mint_authority: Pubkey,
+        freeze_authority: Option<Pubkey>,
+    },
+    GetAccountDataSize {
+        mint: Pubkey,
+    },
+    InitializeImmutableOwner {
+        account: Pubkey,
+    },
+    AmountToUiAmount {
+        mint: Pubkey,
+        amount: u64,
+    },
+    UiAmountToAmount {
+        mint: Pubkey,
+        ui_amount: String,
+    },
+    /// Token-2022 only
+    InitializeMintCloseAuthority {
+        mint: Pubkey,
+        close_authority: Option<Pubkey>,
+    },
+    /// Token-2022 only, the transfer fee extension instruction that moves tokens
+    TransferCheckedWithFee {
+        source: Pubkey,
+        mint: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        decimals: u8,
+        fee: u64,
+    },
+    /// Token-2022 only
+    Reallocate {
+        account: Pubkey,
+        payer: Pubkey,
+        owner: Pubkey,
+    },
+    /// Token-2022 only
+    CreateNativeMint {
+        payer: Pubkey,
+        native_mint: Pubkey,
+    },
+    /// Token-2022 only
+    InitializeNonTransferableMint {
+        mint: Pubkey,
+    },
+    /// Token-2022 only
+    InitializePermanentDelegate {
+        mint: Pubkey,
+        delegate: Pubkey,
+    },
+    /// Token-2022 only
+    WithdrawExcessLamports {
+        source: Pubkey,
+        destination: Pubkey,
+        authority: Pubkey,
+    },
+    /// Any other Token-2022 extension instruction. `instruction` is the extension's own
+    /// sub-instruction discriminant and `data` the remaining undecoded bytes.
+    Extension {
+        extension: TokenExtension,
+        instruction: u8,
+        accounts: Vec<Pubkey>,
+        data: Vec<u8>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthorityType {
+    MintTokens,
+    FreezeAccount,
+    AccountOwner,
+    CloseAccount,
+    TransferFeeConfig,
+    WithheldWithdraw,
+    CloseMint,
+    InterestRate,
+    PermanentDelegate,
+    ConfidentialTransferMint,
+    TransferHookProgramId,
+    ConfidentialTransferFeeConfig,
+    MetadataPointer,
+    GroupPointer,
+    GroupMemberPointer,
+    ScaledUiAmount,
+    Pause,
+}
+
+impl AuthorityType {
+    fn from_u8(value: u8) -> SolProbeResult<Self> {
+        let authority_type = match value {
+            0 => AuthorityType::MintTokens,
+            1 => AuthorityType::FreezeAccount,
+            2 => AuthorityType::AccountOwner,
+            3 => AuthorityType::CloseAccount,
+            4 => AuthorityType::TransferFeeConfig,
+            5 => AuthorityType::WithheldWithdraw,
+            6 => AuthorityType::CloseMint,
+            7 => AuthorityType::InterestRate,
+            8 => AuthorityType::PermanentDelegate,
+            9 => AuthorityType::ConfidentialTransferMint,
+            10 => AuthorityType::TransferHookProgramId,
+            11 => AuthorityType::ConfidentialTransferFeeConfig,
+            12 => AuthorityType::MetadataPointer,
+            13 => AuthorityType::GroupPointer,
+            14 => AuthorityType::GroupMemberPointer,
+            15 => AuthorityType::ScaledUiAmount,
+            16 => AuthorityType::Pause,
+            _ => return Err(SolProbeError::InvalidInstructionData),
+        };
+
+        Ok(authority_type)
+    }
+}
+
+/// Token-2022 extensions whose instructions are namespaced under a single discriminant
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenExtension {
+    TransferFee,
+    ConfidentialTransfer,
+    DefaultAccountState,
+    MemoTransfer,
+    InterestBearingMint,
+    CpiGuard,
+    TransferHook,
+    ConfidentialTransferFee,
+    MetadataPointer,
+    GroupPointer,
+    GroupMemberPointer,
+    ConfidentialMintBurn,
+    ScaledUiAmount,
+    Pausable,
+}
+
+impl TokenInstruction {
+    pub fn is_token_program(program_id: &str) -> bool {
+        program_id == TOKEN_PROGRAM_ID || program_id == TOKEN_2022_PROGRAM_ID
+    }
+
+    pub fn decode(instruction: &Instruction) -> SolProbeResult<Self> {
+        if !TokenInstruction::is_token_program(&instruction.program_id.to_string()) {
+            return Err(SolProbeError::ProgramIdMismatch);
+        }
+
+        let bytes = instruction.data_bytes()?;
+        let mut data = InstructionData::new(&bytes);
+        let account = |index: usize| instruction.account(index);
+        let signers = |from: usize| instruction.accounts.iter().skip(from).copied().collect();
+
+        let discriminant = data.u8()?;
+        // The legacy program ends at UiAmountToAmount, the rest are Token-2022 instructions
+        if discriminant > 24 && instruction.program_id.to_string() != TOKEN_2022_PROGRAM_ID {
+            return Err(SolProbeError::InvalidInstructionData);
+        }
+
+        let decoded = match discriminant {
+            0 => TokenInstruction::InitializeMint {
+                mint: account(0)?,
+                decimals: data.u8()?,
+                mint_authority: data.pubkey()?,
+                freeze_authority: data.option_pubkey()?,
+            },
+            1 => TokenInstruction::InitializeAccount {
+                account: account(0)?,
+                mint: account(1)?,
+                owner: account(2)?,
+            },
+            2 => TokenInstruction::InitializeMultisig {
+                multisig: account(0)?,
+                signers: signers(2),
+                m: data.u8()?,
+            },
+            3 => TokenInstruction::Transfer {
+                source: account(0)?,
+                destination: account(1)?,
+                authority: account(2)?,
+                amount: data.u64()?,
+            },
+            4 => TokenInstruction::Approve {
+                source: account(0)?,
+                delegate: account(1)?,
+                owner: account(2)?,
+                amount: data.u64()?,
+            },
+            5 => TokenInstruction::Revoke {
+                source: account(0)?,
+                owner: account(1)?,
+            },
+            6 => TokenInstruction::SetAuthority {
+                account: account(0)?,
+                current_authority: account(1)?,
+                authority_type: AuthorityType::from_u8(data.u8()?)?,
+                new_authority: data.option_pubkey()?,
+            },
+            7 => TokenInstruction::MintTo {
+                mint: account(0)?,
+                account: account(1)?,
+                mint_authority: account(2)?,
+                amount: data.u64()?,
+            },
+            8 => TokenInstruction::Burn {
+                account: account(0)?,
+                mint: account(1)?,
+                authority: account(2)?,
+                amount: data.u64()?,
+            },
+            9 => TokenInstruction::CloseAccount {
+                account: account(0)?,
+                destination: account(1)?,
+                owner: account(2)?,
+            },
+            10 => TokenInstruction::FreezeAccount {
+                account: account(0)?,
+                mint: account(1)?,
+                freeze_authority: account(2)?,
+            },
+            11 => TokenInstruction::ThawAccount {
+                account: account(0)?,
+                mint: account(1)?,
+                freeze_authority: account(2)?,
+            },
+            12 => TokenInstruction::TransferChecked {
+                source: account(0)?,
+                mint: account(1)?,
+                destination: account(2)?,
+                authority: account(3)?,
+                amount: data.u64()?,
+                decimals: data.u8()?,
+            },
+            13 => TokenInstruction::ApproveChecked {
+                source: account(0)?,
+                mint: account(1)?,
+                delegate: account(2)?,
+                owner: account(3)?,
+                amount: data.u64()?,
+                decimals: data.u8()?,
+            },
+            14 => TokenInstruction::MintToChecked {
+                mint: account(0)?,
+                account: account(1)?,
+                mint_authority: account(2)?,
+                amount: data.u64()?,
+                decimals: data.u8()?,
+            },
+            15 => TokenInstruction::BurnChecked {
+                account: account(0)?,
+                mint: account(1)?,
+                authority: account(2)?,
+                amount: data.u64()?,
+                decimals: data.u8()?,
+            },
+            16 => TokenInstruction::InitializeAccount2 {
+                account: account(0)?,
+                mint: account(1)?,
+                owner: data.pubkey()?,
+            },
+            17 => TokenInstruction::SyncNative {
+                account: account(0)?,
+            },
+            18 => TokenInstruction::InitializeAccount3 {
+                account: account(0)?,
+                mint: account(1)?,
+                owner: data.pubkey()?,
+            },
+            19 => TokenInstruction::InitializeMultisig2 {
+                multisig: account(0)?,
+                signers: signers(1),
+                m: data.u8()?,
+            },
+            20 => TokenInstruction::InitializeMint2 {
+                mint: account(0)?,
+                decimals: data.u8()?,
+                mint_authority: data.pubkey()?,
+                freeze_authority: data.option_pubkey()?,
+            },
+            21 => TokenInstruction::GetAccountDataSize { mint: account(0)? },
+            22 => TokenInstruction::InitializeImmutableOwner {
+                account: account(0)?,
+            },
+            23 => TokenInstruction::AmountToUiAmount {
+                mint: account(0)?,
+                amount: data.u64()?,
+            },
+            24 => TokenInstruction::UiAmountToAmount {
+                mint: account(0)?,
+                ui_amount: String::from_utf8(data.rest().to_vec())
+                    .map_err(|_| SolProbeError::InvalidInstructionData)?,
+            },
+            25 => TokenInstruction::InitializeMintCloseAuthority {
+                mint: account(0)?,
+                close_authority: data.option_pubkey()?,
+            },
+            26 => match data.u8()? {
+                // TransferFeeInstruction::TransferCheckedWithFee
+                1 => TokenInstruction::TransferCheckedWithFee {
+                    source: account(0)?,
+                    mint: account(1)?,
+                    destination: account(2)?,
+                    authority: account(3)?,
+                    amount: data.u64()?,
+                    decimals: data.u8()?,
+                    fee: data.u64()?,
+                },
+                sub_instruction => TokenInstruction::extension(
+                    TokenExtension::TransferFee,
+                    sub_instruction,
+                    instruction,
+                    &mut data,
+                ),
+            },
+            27 => TokenInstruction::extension(
+                TokenExtension::ConfidentialTransfer,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            28 => TokenInstruction::extension(
+                TokenExtension::DefaultAccountState,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            29 => TokenInstruction::Reallocate {
+                account: account(0)?,
+                payer: account(1)?,
+                owner: account(3)?,
+            },
+            30 => TokenInstruction::extension(
+                TokenExtension::MemoTransfer,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            31 => TokenInstruction::CreateNativeMint {
+                payer: account(0)?,
+                native_mint: account(1)?,
+            },
+            32 => TokenInstruction::InitializeNonTransferableMint { mint: account(0)? },
+            33 => TokenInstruction::extension(
+                TokenExtension::InterestBearingMint,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            34 => TokenInstruction::extension(
+                TokenExtension::CpiGuard,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            35 => TokenInstruction::InitializePermanentDelegate {
+                mint: account(0)?,
+                delegate: data.pubkey()?,
+            },
+            36 => TokenInstruction::extension(
+                TokenExtension::TransferHook,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            37 => TokenInstruction::extension(
+                TokenExtension::ConfidentialTransferFee,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            38 => TokenInstruction::WithdrawExcessLamports {
+                source: account(0)?,
+                destination: account(1)?,
+                authority: account(2)?,
+            },
+            39 => TokenInstruction::extension(
+                TokenExtension::MetadataPointer,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            40 => TokenInstruction::extension(
+                TokenExtension::GroupPointer,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            41 => TokenInstruction::extension(
+                TokenExtension::GroupMemberPointer,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            42 => TokenInstruction::extension(
+                TokenExtension::ConfidentialMintBurn,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            43 => TokenInstruction::extension(
+                TokenExtension::ScaledUiAmount,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            44 => TokenInstruction::extension(
+                TokenExtension::Pausable,
+                data.u8()?,
+                instruction,
+                &mut data,
+            ),
+            _ => return Err(SolProbeError::InvalidInstructionData),
+        };
+
+        Ok(decoded)
+    }
+
+    fn extension(
+        extension: TokenExtension,
+        sub_instruction: u8,
+        instruction: &Instruction,
+        data: &mut InstructionData,
+    ) -> Self {
+        TokenInstruction::Extension {
+            extension,
+            instruction: sub_instruction,
+            accounts: instruction.accounts.clone(),
+            data: data.rest().to_vec(),
+        }
+    }
+
+    /// The tokens moved by this instruction, if it moves any
+    pub fn movement(&self) -> Option<TokenMovement> {
+        let movement = match self.clone() {
+            TokenInstruction::Transfer {
+                source,
+                destination,
+                authority,
+                amount,
+            } => TokenMovement {
+                kind: TokenMovementKind::Transfer,
+                source: Some(source),
+                destination: Some(destination),
+                mint: None,
+                authority,
+                amount,
+                decimals: None,
+            },
+            TokenInstruction::TransferChecked {
+                source,
+                mint,
+                destination,
+                authority,
+                amount,
+                decimals,
+            }
+            | TokenInstruction::TransferCheckedWithFee {
+                source,
+                mint,
+                destination,
+                authority,
+                amount,
+                decimals,
+                ..
+            } => TokenMovement {
+                kind: TokenMovementKind::Transfer,
+                source: Some(source),
+                destination: Some(destination),
+                mint: Some(mint),
+                authority,
+                amount,
+                decimals: Some(decimals),
+            },
+            TokenInstruction::MintTo {
+                mint,
+                account,
+                mint_authority,
+                amount,
+            } => TokenMovement {
+                kind: TokenMovementKind::MintTo,
+                source: None,
+                destination: Some(account),
+                mint: Some(mint),
+                authority: mint_authority,
+                amount,
+                decimals: None,
+            },
+            TokenInstruction::MintToChecked {
+                mint,
+                account,
+                mint_authority,
+                amount,
+                decimals,
+            } => TokenMovement {
+                kind: TokenMovementKind::MintTo,
+                source: None,
+                destination: Some(account),
+                mint: Some(mint),
+                authority: mint_authority,
+                amount,
+                decimals: Some(decimals),
+            },
+            TokenInstruction::Burn {
+                account,
+                mint,
+                authority,
+                amount,
+            } => TokenMovement {
+                kind: TokenMovementKind::Burn,
+                source: Some(account),
+                destination: None,
+                mint: Some(mint),
+                authority,
+                amount,
+                decimals: None,
+            },
+            TokenInstruction::BurnChecked {
+                account,
+                mint,
+                authority,
+                amount,
+                decimals,
+            } => TokenMovement {
+                kind: TokenMovementKind::Burn,
+                source: Some(account),
+                destination: None,
+                mint: Some(mint),
+                authority,
+                amount,
+                decimals: Some(decimals),
+            },
+            _ => return None,
+        };
+
+        Some(movement)
+    }
+}
+
+/// Registers `TokenInstruction` decoding for either `TOKEN_PROGRAM_ID` or `TOKEN_2022_PROGRAM_ID`
+/// with a `ProgramDecoderRegistry`
+pub struct TokenDecoder {
+    program_id: &'static str,
+}
+
+impl TokenDecoder {
+    pub fn new(program_id: &'static str) -> Self {
+        Self { program_id }
+    }
+}
+
+impl ProgramDecoder for TokenDecoder {
+    fn program_id(&self) -> &str {
+        self.program_id
+    }
+
+    fn name(&self) -> &str {
+        match self.program_id {
+            TOKEN_2022_PROGRAM_ID => "spl-token-2022",
+            _ => "spl-token",
+        }
+    }
+
+    fn decode(&self, instruction: &Instruction) -> SolProbeResult<serde_json::Value> {
+        serde_json::to_value(TokenInstruction::decode(instruction)?)
+            .map_err(|_| SolProbeError::InvalidInstructionData)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenMovementKind {
+    Transfer,
+    MintTo,
+    Burn,
+}
+
+/// Tokens moved by a single token program instruction, amounts are in the mint's base units
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMovement {
+    pub kind: TokenMovementKind,
+    /// Token account debited, `None` for mints
+    pub source: Option<Pubkey>,
+    /// Token account credited, `None` for burns
+    pub destination: Option<Pubkey>,
+    /// Only known when the instruction references the mint, a plain `Transfer` does not
+    pub mint: Option<Pubkey>,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub decimals: Option<u8>,
+}
+
+impl Transaction {
+    /// Every token transfer, mint and burn executed by the transaction, including the ones
+    /// made through cross-program invocations, in execution order. Failed transactions
+    /// move no tokens.
+    pub fn token_movements(&self) -> Vec<TokenMovement> {
+        if let Some(Some(_)) = self.metadata.as_ref().map(|metadata| &metadata.err) {
+            return Vec::default();
+        }
+
+        self.message
+            .instructions
+            .iter()
+            .flat_map(|instruction| {
+                core::iter::once(instruction).chain(instruction.inner_instructions.iter())
+            })
+            .filter(|instruction| {
+                TokenInstruction::is_token_program(&instruction.program_id.to_string())
+            })
+            .filter_map(|instruction| TokenInstruction::decode(instruction).ok())
+            .filter_map(|instruction| instruction.movement())
+            .collect()
+    }
+}
+
+#[test]
+fn decode_token_instructions() {
+    use crate::{Lamports, Message, Metadata, TransactionError};
+
+    let source: Pubkey = "Source1111111111111111111111111111111111111"
+        .parse()
+        .unwrap();
+    let mint: Pubkey = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+        .parse()
+        .unwrap();
+    let destination: Pubkey = "Destination11111111111111111111111111111111"
+        .parse()
+        .unwrap();
+    let authority: Pubkey = "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe"
+        .parse()
+        .unwrap();
+    let token_instruction = |program_id: &str, data: &[u8]| Instruction {
+        program_id: program_id.parse().unwrap(),
+        accounts: vec![source, mint, destination, authority],
+        data: bs58::encode(data).into_string(),
+        ..Instruction::default()
+    };
+
+    let mut data = vec![12u8];
+    data.extend_from_slice(&2_500_000u64.to_le_bytes());
+    data.push(6);
+    let transfer_checked = token_instruction(TOKEN_PROGRAM_ID, &data);
+
+    let decoded = TokenInstruction::decode(&transfer_checked).unwrap();
+    let transfer = TokenMovement {
+        kind: TokenMovementKind::Transfer,
+        source: Some(source),
+        destination: Some(destination),
+        mint: Some(mint),
+        authority,
+        amount: 2_500_000,
+        decimals: Some(6),
+    };
+    assert_eq!(decoded.movement(), Some(transfer.clone()));
+
+    // TransferFeeInstruction::TransferCheckedWithFee moves the amount, fee included
+    let mut data = vec![26u8, 1];
+    data.extend_from_slice(&2_500_000u64.to_le_bytes());
+    data.push(6);
+    data.extend_from_slice(&2_500u64.to_le_bytes());
+    let with_fee = token_instruction(TOKEN_2022_PROGRAM_ID, &data);
+    assert_eq!(
+        TokenInstruction::decode(&with_fee).unwrap(),
+        TokenInstruction::TransferCheckedWithFee {
+            source,
+            mint,
+            destination,
+            authority,
+            amount: 2_500_000,
+            decimals: 6,
+            fee: 2_500,
+        }
+    );
+
+    // MemoTransferInstruction::Enable
+    let memo_transfer = token_instruction(TOKEN_2022_PROGRAM_ID, &[30, 0]);
+    let decoded = TokenInstruction::decode(&memo_transfer).unwrap();
+    assert!(matches!(
+        decoded,
+        TokenInstruction::Extension {
+            extension: TokenExtension::MemoTransfer,
+            instruction: 0,
+            ref data,
+            ..
+        } if data.is_empty()
+    ));
+    assert_eq!(decoded.movement(), None);
+
+    // The legacy program has no extensions
+    assert_eq!(
+        TokenInstruction::decode(&token_instruction(TOKEN_PROGRAM_ID, &[30, 0])),
+        Err(SolProbeError::InvalidInstructionData)
+    );
+
+    let mut top_level = transfer_checked;
+    top_level.inner_instructions = vec![with_fee];
+    let mut transaction = Transaction {
+        signatures: Vec::default(),
+        message: Message {
+            instructions: vec![top_level, memo_transfer],
+            ..Message::default()
+        },
+        metadata: None,
+    };
+    let movements = transaction.token_movements();
+    assert_eq!(movements.len(), 2);
+    assert_eq!(movements[0], transfer);
+    assert_eq!(movements[1].decimals, Some(6));
+
+    // Failed transactions move no tokens
+    transaction.metadata = Some(Metadata {
+        err: Some(TransactionError::AccountInUse),
+        fee: Lamports(5000),
+        pre_balances: Vec::default(),
+        post_balances: Vec::default(),
+        log_messages: Vec::default(),
+        pre_token_balances: Vec::default(),
+        post_token_balances: Vec::default(),
+        rewards: Vec::default(),
+    });
+    assert_eq!(transaction.token_movements(), Vec::default());
+}
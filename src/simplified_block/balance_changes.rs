@@ -0,0 +1,302 @@
+use crate::{
+    Lamports, LamportsDelta, Pubkey, SolProbeError, SolProbeResult, TokenBalance, TokenQuantity,
+    Transaction,
+};
+use core::convert::TryFrom;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How a transaction changed the SOL balance of one of its accounts
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChange {
+    pub pubkey: Pubkey,
+    pub signer: bool,
+    pub writable: bool,
+    /// The first signer pays the transaction fee
+    pub fee_payer: bool,
+    pub pre_balance: Lamports,
+    pub post_balance: Lamports,
+    /// `post_balance - pre_balance`, fee included
+    pub change: LamportsDelta,
+    /// The transaction fee, only ever non-zero for the fee payer
+    pub fee: Lamports,
+    /// The change caused by the instructions themselves, `change` with the fee added back
+    pub transfer: LamportsDelta,
+}
+
+/// How a transaction changed the token balance an owner holds of a mint, in base units
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalanceChange {
+    /// Owner of the token accounts. Falls back to the token account address when the node
+    /// did not report the owner
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub program_id: Option<Pubkey>,
+    /// Sum over the owner's token accounts of that mint before the transaction
+    pub pre_amount: TokenQuantity,
+    /// Sum over the owner's token accounts of that mint after the transaction
+    pub post_amount: TokenQuantity,
+    /// `post_amount - pre_amount` in base units
+    pub change: i128,
+}
+
+impl Transaction {
+    /// The lamport balance change of every account of the transaction in the order of
+    /// `Message::resolved_account_keys`. Empty when the block was fetched without metadata,
+    /// fails when the balances do not match the accounts one to one.
+    pub fn balance_changes(&self) -> SolProbeResult<Vec<BalanceChange>> {
+        let metadata = match &self.metadata {
+            Some(metadata) => metadata,
+            None => return Ok(Vec::default()),
+        };
+        let account_keys = self.message.resolved_account_keys();
+        if metadata.pre_balances.len() != account_keys.len()
+            || metadata.post_balances.len() != account_keys.len()
+        {
+            return Err(SolProbeError::InvalidAccountIndex);
+        }
+
+        account_keys
+            .into_iter()
+            .zip(metadata.pre_balances.iter())
+            .zip(metadata.post_balances.iter())
+            .enumerate()
+            .map(|(index, ((pubkey, pre_balance), post_balance))| {
+                let fee_payer = index == 0;
+                let fee = if fee_payer {
+                    metadata.fee
+                } else {
+                    Lamports::ZERO
+                };
+                let change = post_balance
+                    .delta(*pre_balance)
+                    .ok_or(SolProbeError::LamportsOverflow)?;
+                let transfer = LamportsDelta::try_from(fee)?
+                    .checked_add(change)
+                    .ok_or(SolProbeError::LamportsOverflow)?;
+
+                Ok(BalanceChange {
+                    pubkey: *pubkey,
+                    signer: self.message.is_signer(index),
+                    writable: self.message.is_writable(index),
+                    fee_payer,
+                    pre_balance: *pre_balance,
+                    post_balance: *post_balance,
+                    change,
+                    fee,
+                    transfer,
+                })
+            })
+            .collect()
+    }
+
+    /// The balance change of a single account, `None` when the account is not part of the transaction
+    pub fn balance_change(&self, pubkey: &Pubkey) -> SolProbeResult<Option<BalanceChange>> {
+        Ok(self
+            .balance_changes()?
+            .into_iter()
+            .find(|balance_change| &balance_change.pubkey == pubkey))
+    }
+
+    /// The token balance change of every (owner, mint) pair touched by the transaction,
+    /// ordered by owner then mint. Token accounts created or closed by the transaction only
+    /// appear in one of the pre and post balances, their missing side counts as zero.
+    pub fn token_balance_changes(&self) -> SolProbeResult<Vec<TokenBalanceChange>> {
+        let metadata = match &self.metadata {
+            Some(metadata) => metadata,
+            None => return Ok(Vec::default()),
+        };
+        let account_keys = self.message.resolved_account_keys();
+        let mut changes: BTreeMap<(Pubkey, Pubkey), TokenBalanceChange> = BTreeMap::default();
+
+        let mut accumulate = |balance: &TokenBalance, post: bool| -> SolProbeResult<()> {
+            let amount = balance.token_amount;
+            let owner = match balance.owner {
+                Some(owner) => owner,
+                None => **account_keys
+                    .get(balance.account_index as usize)
+                    .ok_or(SolProbeError::InvalidAccountIndex)?,
+            };
+
+            let change =
+                changes
+                    .entry((owner, balance.mint))
+                    .or_insert_with(|| TokenBalanceChange {
+                        owner,
+                        mint: balance.mint,
+                        program_id: balance.program_id,
+                        pre_amount: TokenQuantity::zero(amount.decimals()),
+                        post_amount: TokenQuantity::zero(amount.decimals()),
+                        change: 0,
+                    });
+            let total = if post {
+                &mut change.post_amount
+            } else {
+                &mut change.pre_amount
+            };
+            *total = total
+                .checked_add(amount)
+                .ok_or(SolProbeError::InvalidTokenAmount)?;
+
+            Ok(())
+        };
+
+        for balance in metadata.pre_token_balances.iter() {
+            accumulate(balance, false)?;
+        }
+        for balance in metadata.post_token_balances.iter() {
+            accumulate(balance, true)?;
+        }
+
+        changes
+            .into_values()
+            .map(|mut change| {
+                change.change = change
+                    .post_amount
+                    .delta(change.pre_amount)
+                    .ok_or(SolProbeError::InvalidTokenAmount)?;
+
+                Ok(change)
+            })
+            .collect::<SolProbeResult<Vec<TokenBalanceChange>>>()
+    }
+}
+
+#[test]
+fn balance_changes_separate_fee_from_transfers() {
+    use crate::{Message, Metadata};
+
+    let mut transaction = Transaction {
+        signatures: Vec::default(),
+        message: Message {
+            account_keys: vec![
+                "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe"
+                    .parse()
+                    .unwrap(),
+                "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+                    .parse()
+                    .unwrap(),
+                "11111111111111111111111111111111".parse().unwrap(),
+            ],
+            num_required_signatures: 1,
+            num_readonly_unsigned_accounts: 1,
+            ..Message::default()
+        },
+        metadata: Some(Metadata {
+            err: None,
+            fee: Lamports(5000),
+            pre_balances: vec![Lamports(1_000_000), Lamports(0), Lamports(1)],
+            post_balances: vec![Lamports(895_000), Lamports(100_000), Lamports(1)],
+            log_messages: Vec::default(),
+            pre_token_balances: Vec::default(),
+            post_token_balances: Vec::default(),
+            rewards: Vec::default(),
+        }),
+    };
+
+    let changes = transaction.balance_changes().unwrap();
+    assert_eq!(changes.len(), 3);
+    assert!(changes[0].fee_payer && changes[0].signer && changes[0].writable);
+    assert_eq!(changes[0].change, LamportsDelta(-105_000));
+    assert_eq!(changes[0].transfer, LamportsDelta(-100_000));
+    assert_eq!(
+        transaction
+            .balance_change(
+                &"AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+                    .parse()
+                    .unwrap()
+            )
+            .unwrap()
+            .map(|change| (change.writable, change.transfer)),
+        Some((true, LamportsDelta(100_000)))
+    );
+    assert!(!changes[2].writable);
+
+    // Balances of accounts the transaction does not list, like ones of a truncated lookup
+    let metadata = transaction.metadata.as_mut().unwrap();
+    metadata.pre_balances.push(Lamports(1));
+    assert_eq!(
+        transaction.balance_changes(),
+        Err(SolProbeError::InvalidAccountIndex)
+    );
+    let metadata = transaction.metadata.as_mut().unwrap();
+    metadata.pre_balances.pop();
+    metadata.post_balances.pop();
+    assert_eq!(
+        transaction.balance_changes(),
+        Err(SolProbeError::InvalidAccountIndex)
+    );
+    transaction
+        .metadata
+        .as_mut()
+        .unwrap()
+        .post_balances
+        .push(Lamports(1));
+
+    // A fee and a balance change that no signed amount can hold
+    let metadata = transaction.metadata.as_mut().unwrap();
+    metadata.fee = Lamports(u64::MAX);
+    assert_eq!(
+        transaction.balance_changes(),
+        Err(SolProbeError::LamportsOverflow)
+    );
+    let metadata = transaction.metadata.as_mut().unwrap();
+    metadata.fee = Lamports(5000);
+    metadata.post_balances[1] = Lamports(u64::MAX);
+    assert_eq!(
+        transaction.balance_changes(),
+        Err(SolProbeError::LamportsOverflow)
+    );
+}
+
+#[test]
+fn token_balance_changes_per_owner_and_mint() {
+    use crate::{Message, Metadata};
+
+    let (alice, bob) = (Pubkey::new([1; 32]), Pubkey::new([2; 32]));
+    let balance = |account_index: u8, owner: Pubkey, amount: u128| TokenBalance {
+        account_index,
+        mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+            .parse()
+            .unwrap(),
+        token_amount: TokenQuantity::new(amount, 6),
+        owner: Some(owner),
+        program_id: Some(
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+                .parse()
+                .unwrap(),
+        ),
+    };
+
+    let transaction = Transaction {
+        signatures: Vec::default(),
+        message: Message::default(),
+        metadata: Some(Metadata {
+            err: None,
+            fee: Lamports(5000),
+            pre_balances: Vec::default(),
+            post_balances: Vec::default(),
+            log_messages: Vec::default(),
+            pre_token_balances: vec![balance(1, alice, u64::MAX as u128)],
+            post_token_balances: vec![
+                balance(1, alice, u64::MAX as u128 - 615),
+                balance(2, bob, 615),
+            ],
+            rewards: Vec::default(),
+        }),
+    };
+
+    let changes = transaction.token_balance_changes().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!((changes[0].owner, changes[0].change), (alice, -615));
+    assert_eq!(
+        (
+            changes[1].owner,
+            changes[1].pre_amount.amount(),
+            changes[1].change
+        ),
+        (bob, 0, 615)
+    );
+}
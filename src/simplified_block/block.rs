@@ -1,12 +1,24 @@
 use crate::solana_blocks::EncodedConfirmedBlock;
-use crate::{Rewards, SolProbeResult, Transaction, UnixTimestamp};
+use crate::{
+    parse_base58, Hash, ProgramDecoderRegistry, Reward, Rewards, Signature, SolProbeResult,
+    Transaction, UnixTimestamp,
+};
+use core::convert::TryFrom;
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// The built-in decoders, shared by every `ConfirmedBlock::decode`
+static BUILT_IN_DECODERS: OnceLock<ProgramDecoderRegistry> = OnceLock::new();
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfirmedBlock {
-    pub blockhash: String,
+    pub blockhash: Hash,
+    /// Empty when the block was fetched with `signatures` or `none` transaction details
     pub transactions: Vec<Transaction>,
+    /// The id of every transaction in the block, in order. Empty when the block was fetched
+    /// with `none` transaction details
+    pub signatures: Vec<Signature>,
     pub rewards: Rewards,
     pub block_time: Option<UnixTimestamp>,
     pub block_height: Option<u64>,
@@ -15,29 +27,55 @@ pub struct ConfirmedBlock {
 impl ConfirmedBlock {
     pub fn new() -> Self {
         Self {
-            blockhash: String::default(),
+            blockhash: Hash::default(),
             transactions: Vec::default(),
+            signatures: Vec::default(),
             rewards: Rewards::default(),
             block_time: Option::default(),
             block_height: Option::default(),
         }
     }
 
+    /// Decodes the block, running the built-in program decoders over every instruction
     pub fn decode(&mut self, block: &EncodedConfirmedBlock) -> SolProbeResult<&mut Self> {
-        self.blockhash = block.blockhash.clone();
+        self.decode_with(
+            block,
+            BUILT_IN_DECODERS.get_or_init(ProgramDecoderRegistry::new),
+        )
+    }
+
+    /// Decodes the block, running the decoders in `registry` over every instruction
+    pub fn decode_with(
+        &mut self,
+        block: &EncodedConfirmedBlock,
+        registry: &ProgramDecoderRegistry,
+    ) -> SolProbeResult<&mut Self> {
+        self.blockhash = block.blockhash.parse()?;
         self.block_height = block.block_height;
-        self.block_time = block.block_time.clone();
-        self.rewards = block.rewards.clone();
+        self.block_time = block.block_time;
+        self.rewards = block
+            .rewards
+            .iter()
+            .cloned()
+            .map(Reward::try_from)
+            .collect::<SolProbeResult<Rewards>>()?;
 
         let mut transactions: Vec<Transaction> = Vec::default();
 
         for tx in block.transactions.iter() {
             let mut transaction = Transaction::new();
-            transaction.decode_tx(&tx)?;
+            transaction.decode_tx(tx)?.decode_programs(registry);
 
             transactions.push(transaction);
         }
 
+        self.signatures = match &block.signatures {
+            Some(signatures) => parse_base58(signatures)?,
+            None => transactions
+                .iter()
+                .filter_map(|transaction| transaction.signatures.first().copied())
+                .collect(),
+        };
         self.transactions = transactions;
 
         Ok(self)
@@ -5,4 +5,124 @@ use serde::{Deserialize, Serialize};
 pub enum SolProbeError {
     UnsupportedEncoding,
     UnsupportedMessageType,
+    UnsupportedTransactionVersion,
+    InvalidBase58,
+    InvalidBase64,
+    MalformedTransaction,
+    InvalidAccountIndex,
+    InvalidInstructionIndex,
+    ProgramIdMismatch,
+    InvalidInstructionData,
+    NotEnoughAccountKeys,
+    InvalidIdl,
+    InvalidTokenAmount,
+    /// A lamport amount does not fit the type it is converted to or summed into
+    LamportsOverflow,
+    InvalidPubkey,
+    InvalidHash,
+    InvalidSignature,
+    /// The transport failed to deliver the request, with the transport's own error message
+    Transport(String),
+    /// The node answered with an HTTP error status, like 429 when rate limited. `retry_after`
+    /// holds the seconds of the `Retry-After` header.
+    Http {
+        status: u16,
+        retry_after: Option<u64>,
+    },
+    InvalidRequest,
+    /// `RpcClient` has a retry policy or a rate limit but no `Timer` to wait with
+    MissingTimer,
+    /// The node's answer is not the JSON-RPC response expected, with the reason
+    InvalidResponse(String),
+    /// The name is not a method of the `RpcRequest` catalog
+    UnknownMethod(String),
+    /// The method is no longer served by Solana nodes and the client denies removed methods
+    RemovedMethod(String),
+    /// Neither a cluster moniker nor an `http://` or `https://` URL
+    UnknownCluster(String),
+    /// The node is not part of the expected cluster, with the genesis hash it reported
+    WrongCluster(crate::Hash),
+    /// The node answered the request with a JSON-RPC error
+    Rpc(crate::RpcError),
+    /// `FixtureRecorder` could not write a fixture, with the I/O error
+    FixtureIo(String),
+}
+
+impl core::fmt::Display for SolProbeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SolProbeError::UnsupportedEncoding => f.write_str("unsupported transaction encoding"),
+            SolProbeError::UnsupportedMessageType => f.write_str("unsupported message type"),
+            SolProbeError::UnsupportedTransactionVersion => {
+                f.write_str("unsupported transaction version")
+            }
+            SolProbeError::InvalidBase58 => f.write_str("invalid base58 string"),
+            SolProbeError::InvalidBase64 => f.write_str("invalid base64 string"),
+            SolProbeError::MalformedTransaction => f.write_str("malformed transaction"),
+            SolProbeError::InvalidAccountIndex => {
+                f.write_str("account index out of range of the account keys")
+            }
+            SolProbeError::InvalidInstructionIndex => {
+                f.write_str("inner instructions refer to a missing instruction")
+            }
+            SolProbeError::ProgramIdMismatch => {
+                f.write_str("instruction belongs to a different program")
+            }
+            SolProbeError::InvalidInstructionData => f.write_str("invalid instruction data"),
+            SolProbeError::NotEnoughAccountKeys => {
+                f.write_str("instruction has fewer accounts than expected")
+            }
+            SolProbeError::InvalidIdl => f.write_str("invalid Anchor IDL"),
+            SolProbeError::InvalidTokenAmount => f.write_str("invalid token amount"),
+            SolProbeError::LamportsOverflow => f.write_str("lamport amount overflows"),
+            SolProbeError::InvalidPubkey => f.write_str("invalid public key"),
+            SolProbeError::InvalidHash => f.write_str("invalid hash"),
+            SolProbeError::InvalidSignature => f.write_str("invalid signature"),
+            SolProbeError::Transport(error) => write!(f, "transport error: {}", error),
+            SolProbeError::Http {
+                status,
+                retry_after: Some(seconds),
+            } => write!(f, "HTTP status {}, retry after {}s", status, seconds),
+            SolProbeError::Http { status, .. } => write!(f, "HTTP status {}", status),
+            SolProbeError::InvalidRequest => f.write_str("request could not be serialized"),
+            SolProbeError::MissingTimer => {
+                f.write_str("retries and rate limits need a timer to wait with")
+            }
+            SolProbeError::InvalidResponse(reason) => {
+                write!(f, "invalid JSON-RPC response: {}", reason)
+            }
+            SolProbeError::UnknownMethod(method) => write!(f, "unknown RPC method `{}`", method),
+            SolProbeError::RemovedMethod(method) => {
+                write!(f, "RPC method `{}` was removed from Solana nodes", method)
+            }
+            SolProbeError::UnknownCluster(cluster) => write!(f, "unknown cluster `{}`", cluster),
+            SolProbeError::WrongCluster(genesis_hash) => {
+                write!(
+                    f,
+                    "node belongs to the cluster with genesis hash {}",
+                    genesis_hash
+                )
+            }
+            SolProbeError::Rpc(error) => write!(f, "RPC error {}: {}", error.code, error.message),
+            SolProbeError::FixtureIo(error) => write!(f, "could not write fixture: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SolProbeError {}
+
+#[test]
+fn errors_display_messages() {
+    assert_eq!(
+        SolProbeError::Http {
+            status: 429,
+            retry_after: Some(2)
+        }
+        .to_string(),
+        "HTTP status 429, retry after 2s"
+    );
+    assert_eq!(
+        SolProbeError::UnknownMethod("getFoo".to_string()).to_string(),
+        "unknown RPC method `getFoo`"
+    );
 }
@@ -1,10 +1,6 @@
 pub type UnixTimestamp = i64;
 
-pub type StringAmount = String;
-
-pub type StringDecimals = String;
-
-pub type Rewards = Vec<crate::solana_blocks::Reward>;
+pub type Rewards = Vec<crate::Reward>;
 
 pub type Slot = u64;
 
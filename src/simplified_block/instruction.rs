@@ -0,0 +1,184 @@
+use crate::solana_blocks::{UiCompiledInstruction, UiInstruction, UiParsedInstruction};
+use crate::{parse_base58, DecodedInstruction, Pubkey, SolProbeError, SolProbeResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Instruction {
+    pub program_id: Pubkey,
+    /// The accounts passed to the program, in the order the program expects them
+    pub accounts: Vec<Pubkey>,
+    /// Base58 encoded instruction data. Empty when the node returned the instruction
+    /// already parsed in a `jsonParsed` block
+    pub data: String,
+    /// Name of the program when the node parsed the instruction
+    pub program: Option<String>,
+    /// The node's own decoding of the instruction for `jsonParsed` blocks
+    pub parsed: Option<serde_json::Value>,
+    /// Output of the `ProgramDecoder` registered for `program_id`, if any
+    pub decoded: Option<DecodedInstruction>,
+    /// Invocation depth of an inner instruction, `1` being the top-level instruction
+    pub stack_height: Option<u32>,
+    /// Instructions invoked by this instruction through cross-program invocations,
+    /// in execution order. Always empty for inner instructions themselves.
+    pub inner_instructions: Vec<Instruction>,
+}
+
+impl Instruction {
+    /// Resolves the account indexes of an instruction against the list returned by
+    /// `Message::resolved_account_keys`
+    pub(crate) fn resolve(
+        instruction: &UiInstruction,
+        account_keys: &[&Pubkey],
+    ) -> SolProbeResult<Self> {
+        match instruction {
+            UiInstruction::Compiled(compiled) => {
+                Instruction::resolve_compiled(compiled, account_keys)
+            }
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => Ok(Self {
+                program_id: parsed.program_id.parse()?,
+                program: Some(parsed.program.clone()),
+                parsed: Some(parsed.parsed.clone()),
+                stack_height: parsed.stack_height,
+                ..Self::default()
+            }),
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(partial)) => Ok(Self {
+                program_id: partial.program_id.parse()?,
+                accounts: parse_base58(&partial.accounts)?,
+                data: partial.data.clone(),
+                stack_height: partial.stack_height,
+                ..Self::default()
+            }),
+        }
+    }
+
+    pub(crate) fn resolve_compiled(
+        instruction: &UiCompiledInstruction,
+        account_keys: &[&Pubkey],
+    ) -> SolProbeResult<Self> {
+        let key = |index: u8| {
+            account_keys
+                .get(index as usize)
+                .map(|key| **key)
+                .ok_or(SolProbeError::InvalidAccountIndex)
+        };
+
+        Ok(Self {
+            program_id: key(instruction.program_id_index)?,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|index| key(*index))
+                .collect::<SolProbeResult<Vec<Pubkey>>>()?,
+            data: instruction.data.clone(),
+            stack_height: instruction.stack_height,
+            ..Self::default()
+        })
+    }
+
+    /// The account passed at `index`, decoders use this to name the accounts by role
+    pub(crate) fn account(&self, index: usize) -> SolProbeResult<Pubkey> {
+        self.accounts
+            .get(index)
+            .copied()
+            .ok_or(SolProbeError::NotEnoughAccountKeys)
+    }
+
+    /// Decodes the base58 `data` into the raw bytes passed to the program
+    pub fn data_bytes(&self) -> SolProbeResult<Vec<u8>> {
+        bs58::decode(&self.data)
+            .into_vec()
+            .map_err(|_| SolProbeError::InvalidBase58)
+    }
+}
+
+#[test]
+fn resolve_instructions_and_inner_instructions() {
+    use crate::solana_blocks::EncodedTransactionWithStatusMeta;
+    use crate::Transaction;
+
+    let encoded: EncodedTransactionWithStatusMeta = serde_json::from_str(
+        r#"{
+            "transaction": {
+                "signatures": ["5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv"],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 2
+                    },
+                    "accountKeys": [
+                        "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe",
+                        "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc",
+                        "11111111111111111111111111111111",
+                        "ComputeBudget111111111111111111111111111111"
+                    ],
+                    "recentBlockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+                    "instructions": [
+                        { "programIdIndex": 3, "accounts": [], "data": "3gJqkocMWaMm" },
+                        { "programIdIndex": 2, "accounts": [0, 1], "data": "3Bxs4h24hBtQy9rw" }
+                    ]
+                }
+            },
+            "meta": {
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [10000, 0, 1, 1],
+                "postBalances": [4999, 1, 1, 1],
+                "innerInstructions": [
+                    {
+                        "index": 1,
+                        "instructions": [
+                            { "programIdIndex": 2, "accounts": [1], "data": "3Bxs4h24hBtQy9rw", "stackHeight": 2 }
+                        ]
+                    }
+                ]
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let mut transaction = Transaction::new();
+    transaction.decode_tx(&encoded).unwrap();
+    let instructions = &transaction.message.instructions;
+
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(
+        instructions[0].program_id.to_string(),
+        "ComputeBudget111111111111111111111111111111"
+    );
+    assert!(instructions[0].inner_instructions.is_empty());
+    assert_eq!(
+        instructions[1].account(0).unwrap().to_string(),
+        "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe"
+    );
+    assert_eq!(
+        instructions[1].account(1).unwrap().to_string(),
+        "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"
+    );
+    assert_eq!(
+        instructions[1].accounts,
+        transaction.message.account_keys[..2]
+    );
+    assert_eq!(instructions[1].inner_instructions.len(), 1);
+    assert_eq!(instructions[1].inner_instructions[0].stack_height, Some(2));
+    assert_eq!(
+        instructions[1].inner_instructions[0].accounts,
+        vec![transaction.message.account_keys[1]]
+    );
+
+    // Inner instructions of `jsonParsed` blocks keep their stack height too
+    let parsed: UiInstruction = serde_json::from_str(
+        r#"{
+            "programId": "11111111111111111111111111111111",
+            "accounts": ["AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"],
+            "data": "3Bxs4h24hBtQy9rw",
+            "stackHeight": 2
+        }"#,
+    )
+    .unwrap();
+    let parsed = Instruction::resolve(&parsed, &[]).unwrap();
+    assert_eq!(parsed.stack_height, Some(2));
+    assert_eq!(parsed.accounts, vec![transaction.message.account_keys[1]]);
+}
@@ -0,0 +1,154 @@
+use crate::{SolProbeError, SolProbeResult};
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use serde::de::{Deserializer, Error as DeError};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Defines a fixed size byte array that is displayed, parsed and serialized as base58
+macro_rules! base58_bytes {
+    ($(#[$docs:meta])* $name:ident, $bytes:expr, $error:ident) => {
+        $(#[$docs])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name([u8; $bytes]);
+
+        impl $name {
+            /// The size of the raw value in bytes
+            pub const BYTES: usize = $bytes;
+
+            pub const fn new(bytes: [u8; $bytes]) -> Self {
+                Self(bytes)
+            }
+
+            pub fn as_bytes(&self) -> &[u8; $bytes] {
+                &self.0
+            }
+
+            pub fn to_bytes(self) -> [u8; $bytes] {
+                self.0
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self([0; $bytes])
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl From<[u8; $bytes]> for $name {
+            fn from(bytes: [u8; $bytes]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = SolProbeError;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                <[u8; $bytes]>::try_from(bytes)
+                    .map(Self)
+                    .map_err(|_| SolProbeError::$error)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = SolProbeError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                // Rejects oversized input before decoding, a valid value never takes more
+                // than two base58 characters per byte
+                if value.len() > $bytes * 2 {
+                    return Err(SolProbeError::$error);
+                }
+
+                let bytes = bs58::decode(value)
+                    .into_vec()
+                    .map_err(|_| SolProbeError::$error)?;
+
+                Self::try_from(bytes.as_slice())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&bs58::encode(self.0).into_string())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+
+                value.parse().map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+base58_bytes!(
+    /// The address of an account or program
+    Pubkey,
+    32,
+    InvalidPubkey
+);
+
+base58_bytes!(
+    /// A SHA-256 hash such as a blockhash
+    Hash,
+    32,
+    InvalidHash
+);
+
+base58_bytes!(
+    /// An ed25519 transaction signature, the first signature of a transaction is its id
+    Signature,
+    64,
+    InvalidSignature
+);
+
+/// Parses the base58 strings of an RPC response, failing on the first malformed one
+pub(crate) fn parse_base58<T: FromStr<Err = SolProbeError>>(
+    values: &[String],
+) -> SolProbeResult<Vec<T>> {
+    values.iter().map(|value| value.parse()).collect()
+}
+
+#[test]
+fn base58_keys_round_trip() {
+    let system: Pubkey = "11111111111111111111111111111111".parse().unwrap();
+    assert_eq!(system, Pubkey::default());
+    assert_eq!(system.to_string(), "11111111111111111111111111111111");
+
+    let signature =
+        "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv";
+    let parsed: Signature = serde_json::from_str(&format!("\"{}\"", signature)).unwrap();
+    assert_eq!(
+        serde_json::to_string(&parsed).unwrap(),
+        format!("\"{}\"", signature)
+    );
+
+    assert_eq!(
+        "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2eu0e".parse::<Pubkey>(),
+        Err(SolProbeError::InvalidPubkey)
+    );
+    assert_eq!(signature.parse::<Hash>(), Err(SolProbeError::InvalidHash));
+    assert!(serde_json::from_str::<Pubkey>("\"not a key\"").is_err());
+}
@@ -0,0 +1,172 @@
+use crate::{SolProbeError, SolProbeResult, TokenQuantity};
+use core::convert::TryFrom;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// The number of decimals of SOL, one lamport being `0.000000001` SOL
+pub const SOL_DECIMALS: u8 = 9;
+
+/// An amount of SOL held as integer lamports. Serializes as the plain lamport number the
+/// RPC uses and displays in SOL, `1234567891` lamports being `1.234567891 SOL`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Lamports(pub u64);
+
+impl Lamports {
+    pub const ZERO: Lamports = Lamports(0);
+
+    pub fn new(lamports: u64) -> Self {
+        Self(lamports)
+    }
+
+    pub fn lamports(&self) -> u64 {
+        self.0
+    }
+
+    /// Parses an exact SOL amount like `"1.5"`, failing when it has more than 9 decimals
+    pub fn from_sol(sol: &str) -> SolProbeResult<Self> {
+        let quantity = TokenQuantity::from_ui_amount(sol, SOL_DECIMALS)?;
+
+        u64::try_from(quantity.amount())
+            .map(Self)
+            .map_err(|_| SolProbeError::InvalidTokenAmount)
+    }
+
+    /// A lossy conversion for display or charting, never use the result for arithmetic
+    pub fn to_sol(&self) -> f64 {
+        TokenQuantity::from(*self).to_f64()
+    }
+
+    /// The amount in SOL without the unit and without trailing zeros, like `"0.000005"`
+    pub fn to_sol_string(&self) -> String {
+        TokenQuantity::from(*self).to_ui_string()
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    /// The signed change from `before` to `self`, `None` when it does not fit an i64
+    pub fn delta(self, before: Self) -> Option<LamportsDelta> {
+        i64::try_from(i128::from(self.0) - i128::from(before.0))
+            .ok()
+            .map(LamportsDelta)
+    }
+}
+
+impl From<u64> for Lamports {
+    fn from(lamports: u64) -> Self {
+        Self(lamports)
+    }
+}
+
+impl From<Lamports> for TokenQuantity {
+    fn from(lamports: Lamports) -> Self {
+        TokenQuantity::new(lamports.0 as u128, SOL_DECIMALS)
+    }
+}
+
+impl fmt::Display for Lamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} SOL", self.to_sol_string())
+    }
+}
+
+/// A signed change of a SOL balance in lamports, such as a balance change or a rent reward
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct LamportsDelta(pub i64);
+
+impl LamportsDelta {
+    pub fn new(lamports: i64) -> Self {
+        Self(lamports)
+    }
+
+    pub fn lamports(&self) -> i64 {
+        self.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// The size of the change regardless of its direction
+    pub fn unsigned_abs(&self) -> Lamports {
+        Lamports(self.0.unsigned_abs())
+    }
+
+    /// A lossy conversion for display or charting, never use the result for arithmetic
+    pub fn to_sol(&self) -> f64 {
+        match self.is_negative() {
+            true => -self.unsigned_abs().to_sol(),
+            false => self.unsigned_abs().to_sol(),
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+}
+
+impl From<i64> for LamportsDelta {
+    fn from(lamports: i64) -> Self {
+        Self(lamports)
+    }
+}
+
+impl TryFrom<Lamports> for LamportsDelta {
+    type Error = SolProbeError;
+
+    fn try_from(lamports: Lamports) -> Result<Self, Self::Error> {
+        i64::try_from(lamports.0)
+            .map(Self)
+            .map_err(|_| SolProbeError::LamportsOverflow)
+    }
+}
+
+impl fmt::Display for LamportsDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_negative() {
+            true => write!(f, "-{}", self.unsigned_abs()),
+            false => write!(f, "{}", self.unsigned_abs()),
+        }
+    }
+}
+
+#[test]
+fn lamports_format_as_sol() {
+    assert_eq!(Lamports(1_234_567_891).to_string(), "1.234567891 SOL");
+    assert_eq!(Lamports(5000).to_string(), "0.000005 SOL");
+    assert_eq!(Lamports(2 * LAMPORTS_PER_SOL).to_string(), "2 SOL");
+    assert_eq!(Lamports::from_sol("1.5"), Ok(Lamports(1_500_000_000)));
+    assert!(Lamports::from_sol("0.0000000001").is_err());
+
+    let change = Lamports(895_000).delta(Lamports(1_000_000)).unwrap();
+    assert_eq!(change, LamportsDelta(-105_000));
+    assert_eq!(Lamports(u64::MAX).delta(Lamports(0)), None);
+    assert_eq!(
+        Lamports(0).delta(Lamports(i64::MAX as u64 + 1)),
+        Some(LamportsDelta(i64::MIN))
+    );
+    assert_eq!(change.to_string(), "-0.000105 SOL");
+    assert_eq!(Lamports(1).checked_sub(Lamports(2)), None);
+    assert_eq!(serde_json::to_string(&Lamports(5000)).unwrap(), "5000");
+}
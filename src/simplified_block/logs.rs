@@ -0,0 +1,225 @@
+use crate::Metadata;
+use serde::{Deserialize, Serialize};
+
+/// The log messages of a transaction arranged as a tree of program invocations
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedLogs {
+    /// Top-level instruction invocations in execution order, each holding its CPIs
+    pub invocations: Vec<ProgramInvocation>,
+    /// The node stopped recording logs because they exceeded its byte limit. Invocations
+    /// after that point are missing and the ones in flight are `InvocationResult::Incomplete`
+    pub truncated: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramInvocation {
+    pub program_id: String,
+    /// Invocation depth as logged by the runtime, `1` for top-level instructions
+    pub depth: u32,
+    /// `Program log:` messages and any other line logged while this program was executing
+    pub logs: Vec<String>,
+    /// `Program data:` payloads, each a space separated list of base64 encoded fields
+    pub data: Vec<String>,
+    /// Base64 encoded `Program return:` data
+    pub return_data: Option<String>,
+    pub compute_units_consumed: Option<u64>,
+    pub compute_units_limit: Option<u64>,
+    pub result: InvocationResult,
+    /// Cross-program invocations made by this program, in execution order
+    pub invocations: Vec<ProgramInvocation>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InvocationResult {
+    Success,
+    /// The error message logged by the runtime
+    Failed(String),
+    /// No result was logged, either because the logs were truncated or the
+    /// transaction was aborted
+    #[default]
+    Incomplete,
+}
+
+impl ParsedLogs {
+    pub fn parse<S: AsRef<str>>(log_messages: &[S]) -> Self {
+        let mut parsed = ParsedLogs::default();
+        let mut stack: Vec<ProgramInvocation> = Vec::default();
+
+        for line in log_messages.iter().map(AsRef::as_ref) {
+            if line == "Log truncated" {
+                parsed.truncated = true;
+                continue;
+            }
+
+            if let Some(message) = line.strip_prefix("Program log: ") {
+                if let Some(current) = stack.last_mut() {
+                    current.logs.push(message.to_string());
+                }
+                continue;
+            }
+
+            if let Some(data) = line.strip_prefix("Program data: ") {
+                if let Some(current) = stack.last_mut() {
+                    current.data.push(data.to_string());
+                }
+                continue;
+            }
+
+            if let Some(return_data) = line.strip_prefix("Program return: ") {
+                if let Some(current) = stack.last_mut() {
+                    current.return_data = return_data.split(' ').nth(1).map(str::to_string);
+                }
+                continue;
+            }
+
+            match ParsedLogs::program_line(line) {
+                Some((program_id, ProgramLine::Invoke(depth))) => {
+                    stack.push(ProgramInvocation {
+                        program_id: program_id.to_string(),
+                        depth,
+                        ..ProgramInvocation::default()
+                    });
+                }
+                Some((_, ProgramLine::Consumed(consumed, limit))) => {
+                    if let Some(current) = stack.last_mut() {
+                        current.compute_units_consumed = Some(consumed);
+                        current.compute_units_limit = Some(limit);
+                    }
+                }
+                Some((_, ProgramLine::Result(result))) => {
+                    if let Some(mut finished) = stack.pop() {
+                        finished.result = result;
+                        ParsedLogs::attach(&mut parsed, &mut stack, finished);
+                    }
+                }
+                None => {
+                    if let Some(current) = stack.last_mut() {
+                        current.logs.push(line.to_string());
+                    }
+                }
+            }
+        }
+
+        while let Some(unfinished) = stack.pop() {
+            ParsedLogs::attach(&mut parsed, &mut stack, unfinished);
+        }
+
+        parsed
+    }
+
+    /// Every invocation in the tree, depth first in execution order
+    pub fn flatten(&self) -> Vec<&ProgramInvocation> {
+        fn visit<'a>(invocation: &'a ProgramInvocation, all: &mut Vec<&'a ProgramInvocation>) {
+            all.push(invocation);
+            invocation
+                .invocations
+                .iter()
+                .for_each(|inner| visit(inner, all));
+        }
+
+        let mut all = Vec::default();
+        self.invocations
+            .iter()
+            .for_each(|invocation| visit(invocation, &mut all));
+
+        all
+    }
+
+    /// The invocation whose failure aborted the transaction, the deepest one that logged an error
+    pub fn failed_invocation(&self) -> Option<&ProgramInvocation> {
+        self.flatten()
+            .into_iter()
+            .filter(|invocation| matches!(invocation.result, InvocationResult::Failed(_)))
+            .max_by_key(|invocation| invocation.depth)
+    }
+
+    fn attach(
+        parsed: &mut ParsedLogs,
+        stack: &mut [ProgramInvocation],
+        invocation: ProgramInvocation,
+    ) {
+        match stack.last_mut() {
+            Some(parent) => parent.invocations.push(invocation),
+            None => parsed.invocations.push(invocation),
+        }
+    }
+
+    fn program_line(line: &str) -> Option<(&str, ProgramLine)> {
+        let (program_id, rest) = line.strip_prefix("Program ")?.split_once(' ')?;
+
+        let parsed = if let Some(depth) = rest.strip_prefix("invoke [") {
+            ProgramLine::Invoke(depth.strip_suffix(']')?.parse().ok()?)
+        } else if rest == "success" {
+            ProgramLine::Result(InvocationResult::Success)
+        } else if let Some(error) = rest.strip_prefix("failed: ") {
+            ProgramLine::Result(InvocationResult::Failed(error.to_string()))
+        } else if let Some(consumed) = rest.strip_prefix("consumed ") {
+            let mut units = consumed.split(' ');
+            let consumed = units.next()?.parse().ok()?;
+            let limit = units.nth(1)?.parse().ok()?;
+
+            ProgramLine::Consumed(consumed, limit)
+        } else {
+            return None;
+        };
+
+        Some((program_id, parsed))
+    }
+}
+
+enum ProgramLine {
+    Invoke(u32),
+    Consumed(u64, u64),
+    Result(InvocationResult),
+}
+
+impl Metadata {
+    pub fn parse_logs(&self) -> ParsedLogs {
+        ParsedLogs::parse(&self.log_messages)
+    }
+}
+
+#[test]
+fn parse_logs_into_call_tree() {
+    let logs = [
+        "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+        "Program ComputeBudget111111111111111111111111111111 success",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+        "Program log: Instruction: Route",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+        "Program log: Instruction: Transfer",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 180000 compute units",
+        "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: insufficient funds",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 20000 of 199850 compute units",
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 failed: custom program error: 0x1",
+    ];
+
+    let parsed = ParsedLogs::parse(&logs);
+    assert!(!parsed.truncated);
+    assert_eq!(parsed.invocations.len(), 2);
+
+    let route = &parsed.invocations[1];
+    assert_eq!(route.logs, vec!["Instruction: Route"]);
+    assert_eq!(route.compute_units_consumed, Some(20000));
+    assert_eq!(route.invocations.len(), 1);
+    assert_eq!(route.invocations[0].depth, 2);
+    assert_eq!(
+        parsed
+            .failed_invocation()
+            .map(|invocation| &invocation.result),
+        Some(&InvocationResult::Failed("insufficient funds".to_string()))
+    );
+
+    let truncated = ParsedLogs::parse(&[
+        "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+        "Log truncated",
+    ]);
+    assert!(truncated.truncated);
+    assert_eq!(
+        truncated.invocations[0].result,
+        InvocationResult::Incomplete
+    );
+}
@@ -6,5 +6,17 @@ mod errors;
 pub use errors::*;
 mod transaction;
 pub use transaction::*;
+mod instruction;
+pub use instruction::*;
+mod logs;
+pub use logs::*;
+mod balance_changes;
+pub use balance_changes::*;
+mod keys;
+pub use keys::*;
+mod lamports;
+pub use lamports::*;
+mod token_quantity;
+pub use token_quantity::*;
 mod global;
 pub use global::*;
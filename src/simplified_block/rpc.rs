@@ -1,11 +1,19 @@
+use crate::{SolProbeError, SolProbeResult};
 use core::fmt;
+use core::str::FromStr;
 
+/// The methods of the Solana JSON-RPC API. Methods the node no longer serves are kept so
+/// that old code still compiles, but are deprecated, see `RpcRequest::status`.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum RpcRequest {
+    #[deprecated(
+        note = "removed in Solana 1.0, the validator no longer exposes node registration over RPC"
+    )]
     DeregisterNode,
     GetAccountInfo,
     GetBalance,
     GetBlock,
+    GetBlockCommitment,
     GetBlockHeight,
     GetBlockProduction,
     GetBlocks,
@@ -14,61 +22,170 @@ pub enum RpcRequest {
     GetClusterNodes,
     GetEpochInfo,
     GetEpochSchedule,
+    #[deprecated(note = "removed in Solana 2.0, use `isBlockhashValid` or `getFeeForMessage`")]
     GetFeeCalculatorForBlockhash,
+    GetFeeForMessage,
+    #[deprecated(
+        note = "removed in Solana 2.0, fees are computed per message, use `getFeeForMessage`"
+    )]
     GetFeeRateGovernor,
+    #[deprecated(note = "removed in Solana 2.0, use `getFeeForMessage`")]
     GetFees,
     GetFirstAvailableBlock,
     GetGenesisHash,
     GetHealth,
+    GetHighestSnapshotSlot,
     GetIdentity,
     GetInflationGovernor,
     GetInflationRate,
     GetInflationReward,
     GetLargestAccounts,
+    GetLatestBlockhash,
     GetLeaderSchedule,
     GetMaxRetransmitSlot,
     GetMaxShredInsertSlot,
     GetMinimumBalanceForRentExemption,
     GetMultipleAccounts,
     GetProgramAccounts,
+    #[deprecated(note = "removed in Solana 2.0, use `getLatestBlockhash`")]
     GetRecentBlockhash,
     GetRecentPerformanceSamples,
+    GetRecentPrioritizationFees,
+    #[deprecated(note = "removed in Solana 2.0, use `getHighestSnapshotSlot`")]
     GetSnapshotSlot,
     GetSignaturesForAddress,
     GetSignatureStatuses,
     GetSlot,
     GetSlotLeader,
     GetSlotLeaders,
+    #[deprecated(note = "removed in Solana 1.1, storage mining was removed")]
     GetStorageTurn,
+    #[deprecated(note = "removed in Solana 1.1, storage mining was removed")]
     GetStorageTurnRate,
+    #[deprecated(note = "removed in Solana 1.1, storage mining was removed")]
     GetSlotsPerSegment,
+    #[deprecated(
+        note = "removed in Solana 2.0, read the stake account and the stake history sysvar instead"
+    )]
     GetStakeActivation,
+    GetStakeMinimumDelegation,
+    #[deprecated(note = "removed in Solana 1.1, storage mining was removed")]
     GetStoragePubkeysForSlot,
     GetSupply,
     GetTokenAccountBalance,
     GetTokenAccountsByDelegate,
     GetTokenAccountsByOwner,
+    GetTokenLargestAccounts,
     GetTokenSupply,
     GetTransaction,
     GetTransactionCount,
     GetVersion,
     GetVoteAccounts,
+    IsBlockhashValid,
     MinimumLedgerSlot,
+    #[deprecated(
+        note = "removed in Solana 1.0, the validator no longer exposes node registration over RPC"
+    )]
     RegisterNode,
     RequestAirdrop,
     SendTransaction,
     SimulateTransaction,
+    #[deprecated(note = "removed in Solana 1.0, the vote signer service was removed")]
     SignVote,
-    Custom { method: &'static str },
+    Custom {
+        method: &'static str,
+    },
 }
 
+/// Whether a method is still served by current Solana nodes
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum MethodStatus {
+    Current,
+    /// No longer served, calling it fails with `RpcErrorKind::MethodNotFound`
+    Removed {
+        since: &'static str,
+        replacement: Option<RpcRequest>,
+    },
+}
+
+#[allow(deprecated)]
 impl RpcRequest {
+    /// Every method of the catalog, `Custom` aside
+    pub const ALL: &'static [RpcRequest] = &[
+        RpcRequest::DeregisterNode,
+        RpcRequest::GetAccountInfo,
+        RpcRequest::GetBalance,
+        RpcRequest::GetBlock,
+        RpcRequest::GetBlockCommitment,
+        RpcRequest::GetBlockHeight,
+        RpcRequest::GetBlockProduction,
+        RpcRequest::GetBlocks,
+        RpcRequest::GetBlocksWithLimit,
+        RpcRequest::GetBlockTime,
+        RpcRequest::GetClusterNodes,
+        RpcRequest::GetEpochInfo,
+        RpcRequest::GetEpochSchedule,
+        RpcRequest::GetFeeCalculatorForBlockhash,
+        RpcRequest::GetFeeForMessage,
+        RpcRequest::GetFeeRateGovernor,
+        RpcRequest::GetFees,
+        RpcRequest::GetFirstAvailableBlock,
+        RpcRequest::GetGenesisHash,
+        RpcRequest::GetHealth,
+        RpcRequest::GetHighestSnapshotSlot,
+        RpcRequest::GetIdentity,
+        RpcRequest::GetInflationGovernor,
+        RpcRequest::GetInflationRate,
+        RpcRequest::GetInflationReward,
+        RpcRequest::GetLargestAccounts,
+        RpcRequest::GetLatestBlockhash,
+        RpcRequest::GetLeaderSchedule,
+        RpcRequest::GetMaxRetransmitSlot,
+        RpcRequest::GetMaxShredInsertSlot,
+        RpcRequest::GetMinimumBalanceForRentExemption,
+        RpcRequest::GetMultipleAccounts,
+        RpcRequest::GetProgramAccounts,
+        RpcRequest::GetRecentBlockhash,
+        RpcRequest::GetRecentPerformanceSamples,
+        RpcRequest::GetRecentPrioritizationFees,
+        RpcRequest::GetSnapshotSlot,
+        RpcRequest::GetSignaturesForAddress,
+        RpcRequest::GetSignatureStatuses,
+        RpcRequest::GetSlot,
+        RpcRequest::GetSlotLeader,
+        RpcRequest::GetSlotLeaders,
+        RpcRequest::GetStorageTurn,
+        RpcRequest::GetStorageTurnRate,
+        RpcRequest::GetSlotsPerSegment,
+        RpcRequest::GetStakeActivation,
+        RpcRequest::GetStakeMinimumDelegation,
+        RpcRequest::GetStoragePubkeysForSlot,
+        RpcRequest::GetSupply,
+        RpcRequest::GetTokenAccountBalance,
+        RpcRequest::GetTokenAccountsByDelegate,
+        RpcRequest::GetTokenAccountsByOwner,
+        RpcRequest::GetTokenLargestAccounts,
+        RpcRequest::GetTokenSupply,
+        RpcRequest::GetTransaction,
+        RpcRequest::GetTransactionCount,
+        RpcRequest::GetVersion,
+        RpcRequest::GetVoteAccounts,
+        RpcRequest::IsBlockhashValid,
+        RpcRequest::MinimumLedgerSlot,
+        RpcRequest::RegisterNode,
+        RpcRequest::RequestAirdrop,
+        RpcRequest::SendTransaction,
+        RpcRequest::SimulateTransaction,
+        RpcRequest::SignVote,
+    ];
+
     pub fn to_camel_case(self) -> &'static str {
         match self {
             RpcRequest::DeregisterNode => "deregisterNode",
             RpcRequest::GetAccountInfo => "getAccountInfo",
             RpcRequest::GetBalance => "getBalance",
             RpcRequest::GetBlock => "getBlock",
+            RpcRequest::GetBlockCommitment => "getBlockCommitment",
             RpcRequest::GetBlockHeight => "getBlockHeight",
             RpcRequest::GetBlockProduction => "getBlockProduction",
             RpcRequest::GetBlocks => "getBlocks",
@@ -78,16 +195,19 @@ impl RpcRequest {
             RpcRequest::GetEpochInfo => "getEpochInfo",
             RpcRequest::GetEpochSchedule => "getEpochSchedule",
             RpcRequest::GetFeeCalculatorForBlockhash => "getFeeCalculatorForBlockhash",
+            RpcRequest::GetFeeForMessage => "getFeeForMessage",
             RpcRequest::GetFeeRateGovernor => "getFeeRateGovernor",
             RpcRequest::GetFees => "getFees",
             RpcRequest::GetFirstAvailableBlock => "getFirstAvailableBlock",
             RpcRequest::GetGenesisHash => "getGenesisHash",
             RpcRequest::GetHealth => "getHealth",
+            RpcRequest::GetHighestSnapshotSlot => "getHighestSnapshotSlot",
             RpcRequest::GetIdentity => "getIdentity",
             RpcRequest::GetInflationGovernor => "getInflationGovernor",
             RpcRequest::GetInflationRate => "getInflationRate",
             RpcRequest::GetInflationReward => "getInflationReward",
             RpcRequest::GetLargestAccounts => "getLargestAccounts",
+            RpcRequest::GetLatestBlockhash => "getLatestBlockhash",
             RpcRequest::GetLeaderSchedule => "getLeaderSchedule",
             RpcRequest::GetMaxRetransmitSlot => "getMaxRetransmitSlot",
             RpcRequest::GetMaxShredInsertSlot => "getMaxShredInsertSlot",
@@ -96,26 +216,30 @@ impl RpcRequest {
             RpcRequest::GetProgramAccounts => "getProgramAccounts",
             RpcRequest::GetRecentBlockhash => "getRecentBlockhash",
             RpcRequest::GetRecentPerformanceSamples => "getRecentPerformanceSamples",
+            RpcRequest::GetRecentPrioritizationFees => "getRecentPrioritizationFees",
             RpcRequest::GetSnapshotSlot => "getSnapshotSlot",
             RpcRequest::GetSignaturesForAddress => "getSignaturesForAddress",
             RpcRequest::GetSignatureStatuses => "getSignatureStatuses",
             RpcRequest::GetSlot => "getSlot",
             RpcRequest::GetSlotLeader => "getSlotLeader",
             RpcRequest::GetSlotLeaders => "getSlotLeaders",
-            RpcRequest::GetStakeActivation => "getStakeActivation",
             RpcRequest::GetStorageTurn => "getStorageTurn",
             RpcRequest::GetStorageTurnRate => "getStorageTurnRate",
             RpcRequest::GetSlotsPerSegment => "getSlotsPerSegment",
+            RpcRequest::GetStakeActivation => "getStakeActivation",
+            RpcRequest::GetStakeMinimumDelegation => "getStakeMinimumDelegation",
             RpcRequest::GetStoragePubkeysForSlot => "getStoragePubkeysForSlot",
             RpcRequest::GetSupply => "getSupply",
             RpcRequest::GetTokenAccountBalance => "getTokenAccountBalance",
             RpcRequest::GetTokenAccountsByDelegate => "getTokenAccountsByDelegate",
             RpcRequest::GetTokenAccountsByOwner => "getTokenAccountsByOwner",
+            RpcRequest::GetTokenLargestAccounts => "getTokenLargestAccounts",
             RpcRequest::GetTokenSupply => "getTokenSupply",
             RpcRequest::GetTransaction => "getTransaction",
             RpcRequest::GetTransactionCount => "getTransactionCount",
             RpcRequest::GetVersion => "getVersion",
             RpcRequest::GetVoteAccounts => "getVoteAccounts",
+            RpcRequest::IsBlockhashValid => "isBlockhashValid",
             RpcRequest::MinimumLedgerSlot => "minimumLedgerSlot",
             RpcRequest::RegisterNode => "registerNode",
             RpcRequest::RequestAirdrop => "requestAirdrop",
@@ -125,71 +249,142 @@ impl RpcRequest {
             RpcRequest::Custom { method } => method,
         }
     }
+
+    /// Whether current nodes still serve this method, `Custom` methods outside the catalog
+    /// are assumed to be
+    pub fn status(self) -> MethodStatus {
+        match self {
+            RpcRequest::Custom { method } => method
+                .parse::<RpcRequest>()
+                .map_or(MethodStatus::Current, RpcRequest::status),
+            RpcRequest::DeregisterNode | RpcRequest::RegisterNode | RpcRequest::SignVote => {
+                MethodStatus::Removed {
+                    since: "1.0",
+                    replacement: None,
+                }
+            }
+            RpcRequest::GetFeeCalculatorForBlockhash => MethodStatus::Removed {
+                since: "2.0",
+                replacement: Some(RpcRequest::IsBlockhashValid),
+            },
+            RpcRequest::GetStakeActivation => MethodStatus::Removed {
+                since: "2.0",
+                replacement: None,
+            },
+            RpcRequest::GetFeeRateGovernor | RpcRequest::GetFees => MethodStatus::Removed {
+                since: "2.0",
+                replacement: Some(RpcRequest::GetFeeForMessage),
+            },
+            RpcRequest::GetRecentBlockhash => MethodStatus::Removed {
+                since: "2.0",
+                replacement: Some(RpcRequest::GetLatestBlockhash),
+            },
+            RpcRequest::GetSnapshotSlot => MethodStatus::Removed {
+                since: "2.0",
+                replacement: Some(RpcRequest::GetHighestSnapshotSlot),
+            },
+            RpcRequest::GetStorageTurn
+            | RpcRequest::GetStorageTurnRate
+            | RpcRequest::GetSlotsPerSegment
+            | RpcRequest::GetStoragePubkeysForSlot => MethodStatus::Removed {
+                since: "1.1",
+                replacement: None,
+            },
+            _ => MethodStatus::Current,
+        }
+    }
+
+    pub fn is_removed(self) -> bool {
+        matches!(self.status(), MethodStatus::Removed { .. })
+    }
+
+    /// Whether sending the request twice has the same effect as sending it once, so that it
+    /// may be retried when the outcome of the first attempt is unknown. `Custom` methods
+    /// outside the catalog are assumed not to be.
+    pub fn is_idempotent(self) -> bool {
+        match self {
+            RpcRequest::SendTransaction
+            | RpcRequest::RequestAirdrop
+            | RpcRequest::RegisterNode
+            | RpcRequest::DeregisterNode
+            | RpcRequest::SignVote => false,
+            RpcRequest::Custom { method } => method
+                .parse::<RpcRequest>()
+                .is_ok_and(RpcRequest::is_idempotent),
+            _ => true,
+        }
+    }
+
+    /// The JSON-RPC 2.0 request object calling this method with `params`, `null` params
+    /// being sent as an empty array
+    pub fn build_request_json(self, id: u64, params: serde_json::Value) -> serde_json::Value {
+        let params = match params {
+            serde_json::Value::Null => serde_json::Value::Array(Vec::default()),
+            params => params,
+        };
+
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": self.to_camel_case(),
+            "params": params,
+        })
+    }
 }
 
 impl fmt::Display for RpcRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let method = match self {
-            RpcRequest::DeregisterNode => "deregisterNode",
-            RpcRequest::GetAccountInfo => "getAccountInfo",
-            RpcRequest::GetBalance => "getBalance",
-            RpcRequest::GetBlock => "getBlock",
-            RpcRequest::GetBlockHeight => "getBlockHeight",
-            RpcRequest::GetBlockProduction => "getBlockProduction",
-            RpcRequest::GetBlocks => "getBlocks",
-            RpcRequest::GetBlocksWithLimit => "getBlocksWithLimit",
-            RpcRequest::GetBlockTime => "getBlockTime",
-            RpcRequest::GetClusterNodes => "getClusterNodes",
-            RpcRequest::GetEpochInfo => "getEpochInfo",
-            RpcRequest::GetEpochSchedule => "getEpochSchedule",
-            RpcRequest::GetFeeCalculatorForBlockhash => "getFeeCalculatorForBlockhash",
-            RpcRequest::GetFeeRateGovernor => "getFeeRateGovernor",
-            RpcRequest::GetFees => "getFees",
-            RpcRequest::GetFirstAvailableBlock => "getFirstAvailableBlock",
-            RpcRequest::GetGenesisHash => "getGenesisHash",
-            RpcRequest::GetHealth => "getHealth",
-            RpcRequest::GetIdentity => "getIdentity",
-            RpcRequest::GetInflationGovernor => "getInflationGovernor",
-            RpcRequest::GetInflationRate => "getInflationRate",
-            RpcRequest::GetInflationReward => "getInflationReward",
-            RpcRequest::GetLargestAccounts => "getLargestAccounts",
-            RpcRequest::GetLeaderSchedule => "getLeaderSchedule",
-            RpcRequest::GetMaxRetransmitSlot => "getMaxRetransmitSlot",
-            RpcRequest::GetMaxShredInsertSlot => "getMaxShredInsertSlot",
-            RpcRequest::GetMinimumBalanceForRentExemption => "getMinimumBalanceForRentExemption",
-            RpcRequest::GetMultipleAccounts => "getMultipleAccounts",
-            RpcRequest::GetProgramAccounts => "getProgramAccounts",
-            RpcRequest::GetRecentBlockhash => "getRecentBlockhash",
-            RpcRequest::GetRecentPerformanceSamples => "getRecentPerformanceSamples",
-            RpcRequest::GetSnapshotSlot => "getSnapshotSlot",
-            RpcRequest::GetSignaturesForAddress => "getSignaturesForAddress",
-            RpcRequest::GetSignatureStatuses => "getSignatureStatuses",
-            RpcRequest::GetSlot => "getSlot",
-            RpcRequest::GetSlotLeader => "getSlotLeader",
-            RpcRequest::GetSlotLeaders => "getSlotLeaders",
-            RpcRequest::GetStakeActivation => "getStakeActivation",
-            RpcRequest::GetStorageTurn => "getStorageTurn",
-            RpcRequest::GetStorageTurnRate => "getStorageTurnRate",
-            RpcRequest::GetSlotsPerSegment => "getSlotsPerSegment",
-            RpcRequest::GetStoragePubkeysForSlot => "getStoragePubkeysForSlot",
-            RpcRequest::GetSupply => "getSupply",
-            RpcRequest::GetTokenAccountBalance => "getTokenAccountBalance",
-            RpcRequest::GetTokenAccountsByDelegate => "getTokenAccountsByDelegate",
-            RpcRequest::GetTokenAccountsByOwner => "getTokenAccountsByOwner",
-            RpcRequest::GetTokenSupply => "getTokenSupply",
-            RpcRequest::GetTransaction => "getTransaction",
-            RpcRequest::GetTransactionCount => "getTransactionCount",
-            RpcRequest::GetVersion => "getVersion",
-            RpcRequest::GetVoteAccounts => "getVoteAccounts",
-            RpcRequest::MinimumLedgerSlot => "minimumLedgerSlot",
-            RpcRequest::RegisterNode => "registerNode",
-            RpcRequest::RequestAirdrop => "requestAirdrop",
-            RpcRequest::SendTransaction => "sendTransaction",
-            RpcRequest::SimulateTransaction => "simulateTransaction",
-            RpcRequest::SignVote => "signVote",
-            RpcRequest::Custom { method } => method,
-        };
+        write!(f, "{}", self.to_camel_case())
+    }
+}
+
+/// Parses the camelCase method name, `getBlock` for example. Names outside the catalog
+/// are rejected, use `RpcRequest::Custom` to call them.
+impl FromStr for RpcRequest {
+    type Err = SolProbeError;
 
-        write!(f, "{}", method)
+    fn from_str(method: &str) -> SolProbeResult<Self> {
+        RpcRequest::ALL
+            .iter()
+            .find(|request| request.to_camel_case() == method)
+            .copied()
+            .ok_or_else(|| SolProbeError::UnknownMethod(method.to_string()))
     }
 }
+
+#[test]
+#[allow(deprecated)]
+fn rpc_request_catalog() {
+    for request in RpcRequest::ALL {
+        assert_eq!(request.to_string().parse::<RpcRequest>(), Ok(*request));
+    }
+
+    assert_eq!(
+        "getLatestBlockhash".parse::<RpcRequest>(),
+        Ok(RpcRequest::GetLatestBlockhash)
+    );
+    assert_eq!(
+        "GetBlock".parse::<RpcRequest>(),
+        Err(SolProbeError::UnknownMethod("GetBlock".to_string()))
+    );
+    assert_eq!(
+        RpcRequest::GetRecentBlockhash.status(),
+        MethodStatus::Removed {
+            since: "2.0",
+            replacement: Some(RpcRequest::GetLatestBlockhash),
+        }
+    );
+    assert!(RpcRequest::GetStorageTurn.is_removed());
+    assert_eq!(
+        RpcRequest::GetFeeRateGovernor.status(),
+        MethodStatus::Removed {
+            since: "2.0",
+            replacement: Some(RpcRequest::GetFeeForMessage),
+        }
+    );
+    assert_eq!(RpcRequest::GetBlock.status(), MethodStatus::Current);
+    assert_eq!(
+        RpcRequest::Custom { method: "getAsset" }.status(),
+        MethodStatus::Current
+    );
+}
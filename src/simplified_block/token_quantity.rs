@@ -0,0 +1,255 @@
+use crate::solana_blocks::UiTokenAmount;
+use crate::{SolProbeError, SolProbeResult};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// An exact token amount held as integer base units together with the mint's decimals.
+/// `1.5` of a mint with 6 decimals is stored as `1_500_000` base units.
+///
+/// Serializes as `{"amount": "1500000", "decimals": 6}`, the amount being a string so
+/// JSON consumers that parse numbers as doubles do not lose precision.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "SerdeTokenQuantity", into = "SerdeTokenQuantity")]
+pub struct TokenQuantity {
+    amount: u128,
+    decimals: u8,
+}
+
+impl TokenQuantity {
+    pub fn new(amount: u128, decimals: u8) -> Self {
+        Self { amount, decimals }
+    }
+
+    pub fn zero(decimals: u8) -> Self {
+        Self::new(0, decimals)
+    }
+
+    /// The amount in base units
+    pub fn amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount == 0
+    }
+
+    /// Parses a decimal string like `"1.5"` into a quantity with `decimals` decimals.
+    /// Fails when the string has more fractional digits than the mint supports.
+    pub fn from_ui_amount(ui_amount: &str, decimals: u8) -> SolProbeResult<Self> {
+        let parsed: TokenQuantity = ui_amount.parse()?;
+
+        parsed
+            .rescale(decimals)
+            .ok_or(SolProbeError::InvalidTokenAmount)
+    }
+
+    /// `None` when the decimals differ or the sum overflows
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.same_decimals(&other)?;
+
+        Some(Self::new(
+            self.amount.checked_add(other.amount)?,
+            self.decimals,
+        ))
+    }
+
+    /// `None` when the decimals differ or `other` is larger than `self`
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.same_decimals(&other)?;
+
+        Some(Self::new(
+            self.amount.checked_sub(other.amount)?,
+            self.decimals,
+        ))
+    }
+
+    pub fn checked_mul(self, factor: u128) -> Option<Self> {
+        Some(Self::new(self.amount.checked_mul(factor)?, self.decimals))
+    }
+
+    /// The signed difference `self - other` in base units, `None` when the decimals differ
+    /// or the difference does not fit an `i128`
+    pub fn delta(self, other: Self) -> Option<i128> {
+        self.same_decimals(&other)?;
+
+        let this = i128::try_from(self.amount).ok()?;
+        let other = i128::try_from(other.amount).ok()?;
+
+        this.checked_sub(other)
+    }
+
+    /// The same quantity expressed with `decimals` decimals, `None` if that loses precision
+    /// or overflows
+    pub fn rescale(self, decimals: u8) -> Option<Self> {
+        let amount = match decimals.cmp(&self.decimals) {
+            Ordering::Equal => self.amount,
+            Ordering::Greater => self
+                .amount
+                .checked_mul(10u128.checked_pow((decimals - self.decimals) as u32)?)?,
+            Ordering::Less => {
+                let divisor = 10u128.checked_pow((self.decimals - decimals) as u32)?;
+                if self.amount % divisor != 0 {
+                    return None;
+                }
+
+                self.amount / divisor
+            }
+        };
+
+        Some(Self::new(amount, decimals))
+    }
+
+    /// The amount formatted like the RPC's `uiAmountString`, without trailing zeros
+    pub fn to_ui_string(&self) -> String {
+        let formatted = self.to_string();
+
+        match formatted.contains('.') {
+            true => formatted
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string(),
+            false => formatted,
+        }
+    }
+
+    /// A lossy conversion for display or charting, never use the result for arithmetic
+    pub fn to_f64(&self) -> f64 {
+        self.to_ui_string().parse().unwrap_or(f64::NAN)
+    }
+
+    fn same_decimals(&self, other: &Self) -> Option<()> {
+        match self.decimals == other.decimals {
+            true => Some(()),
+            false => None,
+        }
+    }
+}
+
+impl PartialOrd for TokenQuantity {
+    /// Quantities are only comparable when they have the same decimals
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.same_decimals(other)?;
+
+        Some(self.amount.cmp(&other.amount))
+    }
+}
+
+impl fmt::Display for TokenQuantity {
+    /// Formats with exactly `decimals` fractional digits, `1500000` with 6 decimals is `1.500000`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.amount.to_string();
+        let decimals = self.decimals as usize;
+
+        if decimals == 0 {
+            return write!(f, "{}", digits);
+        }
+
+        let padded = format!("{:0>width$}", digits, width = decimals + 1);
+        let (whole, fraction) = padded.split_at(padded.len() - decimals);
+
+        write!(f, "{}.{}", whole, fraction)
+    }
+}
+
+impl FromStr for TokenQuantity {
+    type Err = SolProbeError;
+
+    /// Parses a decimal string, the decimals being the number of fractional digits so
+    /// `"1.50"` is `150` with 2 decimals. This round-trips with `Display`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (whole, fraction) = match value.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (value, ""),
+        };
+
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            return Err(SolProbeError::InvalidTokenAmount);
+        }
+
+        let decimals =
+            u8::try_from(fraction.len()).map_err(|_| SolProbeError::InvalidTokenAmount)?;
+        let amount = format!("{}{}", whole, fraction)
+            .parse()
+            .map_err(|_| SolProbeError::InvalidTokenAmount)?;
+
+        Ok(Self::new(amount, decimals))
+    }
+}
+
+impl TryFrom<UiTokenAmount> for TokenQuantity {
+    type Error = SolProbeError;
+
+    fn try_from(value: UiTokenAmount) -> Result<Self, Self::Error> {
+        let amount = value
+            .amount
+            .parse()
+            .map_err(|_| SolProbeError::InvalidTokenAmount)?;
+
+        Ok(Self::new(amount, value.decimals))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SerdeTokenQuantity {
+    amount: String,
+    decimals: u8,
+}
+
+impl From<TokenQuantity> for SerdeTokenQuantity {
+    fn from(value: TokenQuantity) -> Self {
+        Self {
+            amount: value.amount.to_string(),
+            decimals: value.decimals,
+        }
+    }
+}
+
+impl TryFrom<SerdeTokenQuantity> for TokenQuantity {
+    type Error = SolProbeError;
+
+    fn try_from(value: SerdeTokenQuantity) -> Result<Self, Self::Error> {
+        let amount = value
+            .amount
+            .parse()
+            .map_err(|_| SolProbeError::InvalidTokenAmount)?;
+
+        Ok(Self::new(amount, value.decimals))
+    }
+}
+
+#[test]
+fn token_quantity_is_lossless() {
+    let supply = TokenQuantity::new(u64::MAX as u128, 9);
+    assert_eq!(supply.to_string(), "18446744073.709551615");
+    assert_eq!("18446744073.709551615".parse(), Ok(supply));
+    assert_eq!(TokenQuantity::new(5, 6).to_string(), "0.000005");
+    assert_eq!(TokenQuantity::new(1_500_000, 6).to_ui_string(), "1.5");
+    assert_eq!(
+        TokenQuantity::from_ui_amount("1.5", 6),
+        Ok(TokenQuantity::new(1_500_000, 6))
+    );
+    assert_eq!(
+        TokenQuantity::from_ui_amount("1.0000005", 6),
+        Err(SolProbeError::InvalidTokenAmount)
+    );
+
+    let one = TokenQuantity::new(1_000_000, 6);
+    assert_eq!(one.checked_sub(supply), None);
+    assert_eq!(one.checked_add(one), Some(TokenQuantity::new(2_000_000, 6)));
+    assert_eq!(TokenQuantity::zero(6).delta(one), Some(-1_000_000));
+    assert!(one > TokenQuantity::zero(6));
+    assert_eq!(one.partial_cmp(&supply), None);
+    assert_eq!(
+        serde_json::to_string(&supply).unwrap(),
+        r#"{"amount":"18446744073709551615","decimals":9}"#
+    );
+}
@@ -1,14 +1,20 @@
 use crate::solana_blocks::{
-    EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionError, UiMessage,
-    UiRawMessage, UiTokenAmount, UiTransaction, UiTransactionStatusMeta, UiTransactionTokenBalance,
+    self, EncodedTransaction, EncodedTransactionWithStatusMeta, ParsedAccount, ParsedAccountSource,
+    TransactionError, TransactionVersion, UiAddressTableLookup, UiInnerInstructions,
+    UiLoadedAddresses, UiMessage, UiParsedMessage, UiRawMessage, UiTransaction,
+    UiTransactionEncoding, UiTransactionStatusMeta, UiTransactionTokenBalance, WireTransaction,
 };
-use crate::{Rewards, SolProbeError, SolProbeResult, StringAmount, StringDecimals};
+use crate::{
+    parse_base58, Instruction, Lamports, LamportsDelta, Pubkey, Rewards, Signature, SolProbeError,
+    SolProbeResult, TokenQuantity,
+};
+use core::convert::{TryFrom, TryInto};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
-    pub signatures: Vec<String>,
+    pub signatures: Vec<Signature>,
     pub message: Message,
     pub metadata: Option<Metadata>,
 }
@@ -26,40 +32,101 @@ impl Transaction {
         &mut self,
         encoded_transaction: &EncodedTransactionWithStatusMeta,
     ) -> SolProbeResult<&mut Self> {
-        let transaction = self.destruct_encoding(&encoded_transaction.transaction)?;
-        let message = self.destruct_ui_message(&transaction.message)?;
-
-        self.signatures = transaction.signatures;
-        self.message = Message {
-            account_keys: message.account_keys,
-            num_required_signatures: message.header.num_required_signatures,
-            num_readonly_signed_accounts: message.header.num_readonly_signed_accounts,
-            num_readonly_unsigned_accounts: message.header.num_readonly_unsigned_accounts,
-        };
-        self.metadata = {
-            match &encoded_transaction.meta {
-                Some(metadata) => Some(metadata.clone().into()),
-                None => None,
+        let metadata = encoded_transaction.meta.as_ref();
+
+        let (signatures, mut message) = match &encoded_transaction.transaction {
+            // Blocks fetched with `accounts` transaction details only carry the account list,
+            // the lookup table accounts are flagged in it and there are no instructions
+            EncodedTransaction::Accounts(accounts) => (
+                accounts.signatures.clone(),
+                Message::from_parsed_accounts(accounts.account_keys.clone())?,
+            ),
+            encoding => {
+                let transaction = self.destruct_encoding(encoding)?;
+                let mut message = self.destruct_ui_message(&transaction.message)?;
+
+                if let Some(loaded_addresses) =
+                    metadata.and_then(|metadata| metadata.loaded_addresses.clone())
+                {
+                    message.loaded_addresses = loaded_addresses.try_into()?;
+                }
+                message.instructions = self.destruct_instructions(
+                    &transaction.message,
+                    metadata.and_then(|metadata| metadata.inner_instructions.as_ref()),
+                    &message.resolved_account_keys(),
+                )?;
+
+                (transaction.signatures, message)
             }
         };
 
+        // Older nodes omit the `version` field, the message then keeps the version its
+        // encoding reports
+        if let Some(version) = &encoded_transaction.version {
+            message.version = version.clone();
+        }
+
+        self.signatures = parse_base58(&signatures)?;
+        self.message = message;
+        self.metadata = metadata
+            .map(|metadata| metadata.clone().try_into())
+            .transpose()?;
+
         Ok(self)
     }
 
     fn destruct_encoding(&self, encoding: &EncodedTransaction) -> SolProbeResult<UiTransaction> {
         match encoding {
             EncodedTransaction::Json(encoded_data) => Ok(encoded_data.clone()),
+            EncodedTransaction::LegacyBinary(blob) => WireTransaction::decode_base58(blob),
+            EncodedTransaction::Binary(blob, UiTransactionEncoding::Base58) => {
+                WireTransaction::decode_base58(blob)
+            }
+            EncodedTransaction::Binary(blob, UiTransactionEncoding::Base64) => {
+                WireTransaction::decode_base64(blob)
+            }
             _ => Err(SolProbeError::UnsupportedEncoding),
         }
     }
 
-    pub fn destruct_ui_message(
+    fn destruct_instructions(
         &self,
         encoding: &UiMessage,
-    ) -> core::result::Result<UiRawMessage, SolProbeError> {
+        inner_instructions: Option<&Vec<UiInnerInstructions>>,
+        account_keys: &[&Pubkey],
+    ) -> SolProbeResult<Vec<Instruction>> {
+        let mut instructions = match encoding {
+            UiMessage::Raw(raw_message) => raw_message
+                .instructions
+                .iter()
+                .map(|instruction| Instruction::resolve_compiled(instruction, account_keys))
+                .collect::<SolProbeResult<Vec<Instruction>>>()?,
+            UiMessage::Parsed(parsed_message) => parsed_message
+                .instructions
+                .iter()
+                .map(|instruction| Instruction::resolve(instruction, account_keys))
+                .collect::<SolProbeResult<Vec<Instruction>>>()?,
+        };
+
+        for inner in inner_instructions.into_iter().flatten() {
+            let outer = instructions
+                .get_mut(inner.index as usize)
+                .ok_or(SolProbeError::InvalidInstructionIndex)?;
+
+            for instruction in inner.instructions.iter() {
+                outer
+                    .inner_instructions
+                    .push(Instruction::resolve(instruction, account_keys)?);
+            }
+        }
+
+        Ok(instructions)
+    }
+
+    pub fn destruct_ui_message(&self, encoding: &UiMessage) -> SolProbeResult<Message> {
         match encoding {
-            UiMessage::Raw(raw_message) => Ok(raw_message.clone()),
-            _ => Err(SolProbeError::UnsupportedMessageType),
+            UiMessage::Raw(raw_message) => raw_message.clone().try_into(),
+            UiMessage::Parsed(parsed_message) => parsed_message.clone().try_into(),
         }
     }
 }
@@ -68,45 +135,44 @@ impl Transaction {
 #[serde(rename_all = "camelCase")]
 pub struct Metadata {
     pub err: Option<TransactionError>,
-    pub fee: u64,
-    pub pre_balances: Vec<u64>,
-    pub post_balances: Vec<u64>,
+    pub fee: Lamports,
+    pub pre_balances: Vec<Lamports>,
+    pub post_balances: Vec<Lamports>,
     pub log_messages: Vec<String>,
     pub pre_token_balances: Vec<TokenBalance>,
     pub post_token_balances: Vec<TokenBalance>,
     pub rewards: Rewards,
 }
 
-impl From<UiTransactionStatusMeta> for Metadata {
-    fn from(value: UiTransactionStatusMeta) -> Self {
-        Self {
+impl TryFrom<UiTransactionStatusMeta> for Metadata {
+    type Error = SolProbeError;
+
+    fn try_from(value: UiTransactionStatusMeta) -> Result<Self, Self::Error> {
+        Ok(Self {
             err: value.err,
-            fee: value.fee,
-            pre_balances: value.pre_balances,
-            post_balances: value.post_balances,
-            log_messages: {
-                match value.log_messages {
-                    Some(messages) => messages,
-                    None => Vec::default(),
-                }
-            },
+            fee: Lamports(value.fee),
+            pre_balances: value.pre_balances.into_iter().map(Lamports).collect(),
+            post_balances: value.post_balances.into_iter().map(Lamports).collect(),
+            log_messages: value.log_messages.unwrap_or_default(),
             pre_token_balances: {
                 match value.pre_token_balances {
-                    Some(balances) => TokenBalance::to_token_balance(balances),
+                    Some(balances) => TokenBalance::to_token_balance(balances)?,
                     None => Vec::default(),
                 }
             },
             post_token_balances: {
                 match value.post_token_balances {
-                    Some(balances) => TokenBalance::to_token_balance(balances),
+                    Some(balances) => TokenBalance::to_token_balance(balances)?,
                     None => Vec::default(),
                 }
             },
-            rewards: match value.rewards {
-                Some(rewards) => rewards,
-                None => Vec::default(),
-            },
-        }
+            rewards: value
+                .rewards
+                .unwrap_or_default()
+                .into_iter()
+                .map(Reward::try_from)
+                .collect::<SolProbeResult<Rewards>>()?,
+        })
     }
 }
 
@@ -114,61 +180,61 @@ impl From<UiTransactionStatusMeta> for Metadata {
 #[serde(rename_all = "camelCase")]
 pub struct TokenBalance {
     pub account_index: u8,
-    pub mint: String,
-    pub token_amount: TokenAmount,
+    pub mint: Pubkey,
+    pub token_amount: TokenQuantity,
+    /// Owner of the token account, `None` when the node did not report it
+    pub owner: Option<Pubkey>,
+    /// The token program that owns the token account, `None` when the node did not report it
+    pub program_id: Option<Pubkey>,
 }
 
-impl From<UiTransactionTokenBalance> for TokenBalance {
-    fn from(value: UiTransactionTokenBalance) -> Self {
-        Self {
+impl TryFrom<UiTransactionTokenBalance> for TokenBalance {
+    type Error = SolProbeError;
+
+    fn try_from(value: UiTransactionTokenBalance) -> Result<Self, Self::Error> {
+        Ok(Self {
             account_index: value.account_index,
-            mint: value.mint,
-            token_amount: value.ui_token_amount.into(),
-        }
+            mint: value.mint.parse()?,
+            token_amount: value.ui_token_amount.try_into()?,
+            owner: value.owner.map(|owner| owner.parse()).transpose()?,
+            program_id: value
+                .program_id
+                .map(|program_id| program_id.parse())
+                .transpose()?,
+        })
     }
 }
 
 impl TokenBalance {
-    pub fn to_token_balance(value: Vec<UiTransactionTokenBalance>) -> Vec<Self> {
-        let mut token_balances: Vec<TokenBalance> = Vec::default();
-        value
-            .into_iter()
-            .for_each(|token_balance| token_balances.push(token_balance.into()));
-
-        token_balances
-    }
-}
-
-impl From<UiTokenAmount> for TokenAmount {
-    fn from(value: UiTokenAmount) -> Self {
-        Self {
-            amount: value.ui_amount,
-            decimals: value.decimals,
-            string_amount: value.amount,
-            string_decimals: value.ui_amount_string,
-        }
+    pub fn to_token_balance(value: Vec<UiTransactionTokenBalance>) -> SolProbeResult<Vec<Self>> {
+        value.into_iter().map(TokenBalance::try_from).collect()
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-#[serde(rename_all = "camelCase")]
-pub struct TokenAmount {
-    pub amount: Option<f64>,
-    pub decimals: u8,
-    pub string_amount: StringAmount,
-    pub string_decimals: StringDecimals,
-}
-
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Reward {
-    pub pubkey: String,
-    pub lamports: i64,
-    pub post_balance: u64, // Account balance in lamports after `lamports` was applied
+    pub pubkey: Pubkey,
+    pub lamports: LamportsDelta,
+    pub post_balance: Lamports, // Account balance after `lamports` was applied
     pub reward_type: Option<RewardType>,
     pub commission: Option<u8>, // Vote account commission when the reward was credited, only present for voting and staking rewards
 }
 
+impl TryFrom<solana_blocks::Reward> for Reward {
+    type Error = SolProbeError;
+
+    fn try_from(value: solana_blocks::Reward) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pubkey: value.pubkey.parse()?,
+            lamports: LamportsDelta(value.lamports),
+            post_balance: Lamports(value.post_balance),
+            reward_type: value.reward_type.map(RewardType::from),
+            commission: value.commission,
+        })
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
 pub enum RewardType {
     Fee,
@@ -177,10 +243,21 @@ pub enum RewardType {
     Voting,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+impl From<solana_blocks::RewardType> for RewardType {
+    fn from(value: solana_blocks::RewardType) -> Self {
+        match value {
+            solana_blocks::RewardType::Fee => RewardType::Fee,
+            solana_blocks::RewardType::Rent => RewardType::Rent,
+            solana_blocks::RewardType::Staking => RewardType::Staking,
+            solana_blocks::RewardType::Voting => RewardType::Voting,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Message {
-    pub account_keys: Vec<String>,
+    pub account_keys: Vec<Pubkey>,
     /// The number of signatures required for this message to be considered valid. The
     /// signatures must match the first `num_required_signatures` of `account_keys`.
     /// NOTE: Serialization-related changes must be paired with the direct read at sigverify.
@@ -192,15 +269,303 @@ pub struct Message {
     pub num_readonly_signed_accounts: u8,
     /// The last num_readonly_unsigned_accounts of the unsigned keys are read-only accounts.
     pub num_readonly_unsigned_accounts: u8,
+    pub version: TransactionVersion,
+    /// Lookup tables referenced by a versioned message, empty for legacy messages
+    pub address_table_lookups: Vec<AddressTableLookup>,
+    /// Accounts resolved from `address_table_lookups`, these are not part of `account_keys`
+    pub loaded_addresses: LoadedAddresses,
+    /// The top-level instructions with their inner instructions nested under them
+    pub instructions: Vec<Instruction>,
 }
 
-impl Default for Message {
-    fn default() -> Self {
-        Self {
-            account_keys: Vec::default(),
-            num_required_signatures: u8::default(),
-            num_readonly_signed_accounts: u8::default(),
-            num_readonly_unsigned_accounts: u8::default(),
+impl Message {
+    /// The full list of accounts the transaction was executed with, the static `account_keys`
+    /// followed by the loaded writable and then the loaded readonly addresses. Account indexes
+    /// in instructions, balances and token balances all refer to this list.
+    pub fn resolved_account_keys(&self) -> Vec<&Pubkey> {
+        self.account_keys
+            .iter()
+            .chain(self.loaded_addresses.writable.iter())
+            .chain(self.loaded_addresses.readonly.iter())
+            .collect()
+    }
+
+    /// Whether the account at `index` of `resolved_account_keys` signed the transaction
+    pub fn is_signer(&self, index: usize) -> bool {
+        index < self.num_required_signatures as usize
+    }
+
+    /// Whether the account at `index` of `resolved_account_keys` was requested as writable.
+    /// For `jsonParsed` blocks the header is approximate, see `Message::from_parsed_accounts`.
+    pub fn is_writable(&self, index: usize) -> bool {
+        let num_signers = self.num_required_signatures as usize;
+        let num_static = self.account_keys.len();
+
+        if index < num_signers {
+            index < num_signers.saturating_sub(self.num_readonly_signed_accounts as usize)
+        } else if index < num_static {
+            index < num_static.saturating_sub(self.num_readonly_unsigned_accounts as usize)
+        } else {
+            index < num_static + self.loaded_addresses.writable.len()
+        }
+    }
+}
+
+impl TryFrom<UiRawMessage> for Message {
+    type Error = SolProbeError;
+
+    fn try_from(value: UiRawMessage) -> Result<Self, Self::Error> {
+        Ok(Self {
+            account_keys: parse_base58(&value.account_keys)?,
+            num_required_signatures: value.header.num_required_signatures,
+            num_readonly_signed_accounts: value.header.num_readonly_signed_accounts,
+            num_readonly_unsigned_accounts: value.header.num_readonly_unsigned_accounts,
+            // Versioned messages always carry a lookup list, even an empty one
+            version: match value.address_table_lookups {
+                Some(_) => TransactionVersion::Number(0),
+                None => TransactionVersion::LEGACY,
+            },
+            address_table_lookups: value
+                .address_table_lookups
+                .unwrap_or_default()
+                .into_iter()
+                .map(AddressTableLookup::try_from)
+                .collect::<SolProbeResult<Vec<AddressTableLookup>>>()?,
+            loaded_addresses: LoadedAddresses::default(),
+            instructions: Vec::default(),
+        })
+    }
+}
+
+impl TryFrom<UiParsedMessage> for Message {
+    type Error = SolProbeError;
+
+    fn try_from(value: UiParsedMessage) -> Result<Self, Self::Error> {
+        let mut message = Message::from_parsed_accounts(value.account_keys)?;
+        if value.address_table_lookups.is_some() {
+            message.version = TransactionVersion::Number(0);
+        }
+        message.address_table_lookups = value
+            .address_table_lookups
+            .unwrap_or_default()
+            .into_iter()
+            .map(AddressTableLookup::try_from)
+            .collect::<SolProbeResult<Vec<AddressTableLookup>>>()?;
+
+        Ok(message)
+    }
+}
+
+impl Message {
+    /// A message without instructions built from the account list of a `jsonParsed` message
+    /// or of `accounts` transaction details.
+    ///
+    /// These lists carry no header so the counts are approximate: they are rebuilt from the
+    /// signer and writable flags the RPC reports, and those flags describe the accounts after
+    /// the runtime demoted them (e.g. a writable program id or reserved account is reported as
+    /// readonly). `is_signer` and `is_writable` then follow the reported flags only as long as
+    /// demoted accounts sit where the header would place readonly keys.
+    fn from_parsed_accounts(accounts: Vec<ParsedAccount>) -> SolProbeResult<Self> {
+        let mut message = Message::default();
+
+        fn increment(count: &mut u8) -> SolProbeResult<()> {
+            *count = count
+                .checked_add(1)
+                .ok_or(SolProbeError::MalformedTransaction)?;
+            Ok(())
+        }
+
+        // The RPC orders the keys the same way as the raw message
+        for account in accounts.into_iter() {
+            let pubkey = account.pubkey.parse()?;
+
+            // Loaded accounts are appended after the static keys and are never signers
+            if account.source == Some(ParsedAccountSource::LookupTable) {
+                message.version = TransactionVersion::Number(0);
+                match account.writable {
+                    true => message.loaded_addresses.writable.push(pubkey),
+                    false => message.loaded_addresses.readonly.push(pubkey),
+                }
+
+                continue;
+            }
+
+            match (account.signer, account.writable) {
+                (true, true) => increment(&mut message.num_required_signatures)?,
+                (true, false) => {
+                    increment(&mut message.num_required_signatures)?;
+                    increment(&mut message.num_readonly_signed_accounts)?;
+                }
+                (false, false) => increment(&mut message.num_readonly_unsigned_accounts)?,
+                (false, true) => (),
+            }
+
+            message.account_keys.push(pubkey);
+        }
+
+        Ok(message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTableLookup {
+    /// Address of the lookup table account
+    pub account_key: Pubkey,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+impl TryFrom<UiAddressTableLookup> for AddressTableLookup {
+    type Error = SolProbeError;
+
+    fn try_from(value: UiAddressTableLookup) -> Result<Self, Self::Error> {
+        Ok(Self {
+            account_key: value.account_key.parse()?,
+            writable_indexes: value.writable_indexes,
+            readonly_indexes: value.readonly_indexes,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedAddresses {
+    pub writable: Vec<Pubkey>,
+    pub readonly: Vec<Pubkey>,
+}
+
+impl TryFrom<UiLoadedAddresses> for LoadedAddresses {
+    type Error = SolProbeError;
+
+    fn try_from(value: UiLoadedAddresses) -> Result<Self, Self::Error> {
+        Ok(Self {
+            writable: parse_base58(&value.writable)?,
+            readonly: parse_base58(&value.readonly)?,
+        })
+    }
+}
+
+#[test]
+fn decode_json_parsed_message() {
+    let encoded: EncodedTransactionWithStatusMeta = serde_json::from_str(
+        r#"{
+            "transaction": {
+                "signatures": ["5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv"],
+                "message": {
+                    "accountKeys": [
+                        { "pubkey": "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe", "signer": true, "writable": true },
+                        { "pubkey": "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc", "signer": false, "writable": true },
+                        { "pubkey": "11111111111111111111111111111111", "signer": false, "writable": false }
+                    ],
+                    "recentBlockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+                    "instructions": [
+                        {
+                            "program": "system",
+                            "programId": "11111111111111111111111111111111",
+                            "parsed": { "type": "transfer", "info": { "lamports": 1 } }
+                        }
+                    ]
+                }
+            },
+            "meta": null
+        }"#,
+    )
+    .unwrap();
+
+    let mut transaction = Transaction::new();
+    transaction.decode_tx(&encoded).unwrap();
+
+    assert_eq!(transaction.message.account_keys.len(), 3);
+    assert_eq!(transaction.message.num_required_signatures, 1);
+    assert_eq!(transaction.message.num_readonly_signed_accounts, 0);
+    assert_eq!(transaction.message.num_readonly_unsigned_accounts, 1);
+
+    // More readonly accounts than a header can count
+    let readonly = ParsedAccount {
+        pubkey: "11111111111111111111111111111111".to_string(),
+        writable: false,
+        signer: false,
+        source: None,
+    };
+    assert_eq!(
+        Message::from_parsed_accounts(vec![readonly; 256]),
+        Err(SolProbeError::MalformedTransaction)
+    );
+}
+
+#[test]
+fn decode_versioned_transaction() {
+    let encoded: EncodedTransactionWithStatusMeta = serde_json::from_str(
+        r#"{
+            "transaction": {
+                "signatures": ["5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv"],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 1
+                    },
+                    "accountKeys": [
+                        "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe",
+                        "11111111111111111111111111111111"
+                    ],
+                    "recentBlockhash": "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N",
+                    "instructions": [],
+                    "addressTableLookups": [
+                        {
+                            "accountKey": "4syr5pBaboZy4cZyF6sys82uGD7jEvoAP2ZMaoich4fZ",
+                            "writableIndexes": [3],
+                            "readonlyIndexes": [7]
+                        }
+                    ]
+                }
+            },
+            "meta": {
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [10000, 1, 0, 0],
+                "postBalances": [5000, 1, 0, 0],
+                "loadedAddresses": {
+                    "writable": ["AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc"],
+                    "readonly": ["SysvarC1ock11111111111111111111111111111111"]
+                }
+            },
+            "version": 0
+        }"#,
+    )
+    .unwrap();
+
+    let mut transaction = Transaction::new();
+    transaction.decode_tx(&encoded).unwrap();
+
+    assert_eq!(transaction.message.version, TransactionVersion::Number(0));
+    assert_eq!(transaction.message.address_table_lookups.len(), 1);
+
+    // A v0 message without lookups from a node that omits the `version` field
+    let mut encoded = encoded;
+    encoded.version = None;
+    if let EncodedTransaction::Json(transaction) = &mut encoded.transaction {
+        if let UiMessage::Raw(message) = &mut transaction.message {
+            message.address_table_lookups = Some(Vec::new());
         }
     }
+    let mut unversioned = Transaction::new();
+    unversioned.decode_tx(&encoded).unwrap();
+    assert_eq!(unversioned.message.version, TransactionVersion::Number(0));
+    assert_eq!(
+        transaction
+            .message
+            .resolved_account_keys()
+            .into_iter()
+            .map(Pubkey::to_string)
+            .collect::<Vec<String>>(),
+        vec![
+            "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe",
+            "11111111111111111111111111111111",
+            "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc",
+            "SysvarC1ock11111111111111111111111111111111",
+        ]
+    );
 }
@@ -1,12 +1,7 @@
 use crate::solana_blocks::{EncodedTransactionWithStatusMeta, Rewards, Slot, UnixTimestamp};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct BlockResponse {
-    pub jsonrpc: String,
-    pub result: EncodedConfirmedBlock,
-}
+pub type BlockResponse = crate::RpcResponse<EncodedConfirmedBlock>;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -14,7 +9,10 @@ pub struct EncodedConfirmedBlock {
     pub previous_blockhash: String,
     pub blockhash: String,
     pub parent_slot: Slot,
+    #[serde(default)] // Absent for `signatures` and `none` transaction details
     pub transactions: Vec<EncodedTransactionWithStatusMeta>,
+    pub signatures: Option<Vec<String>>, // Only present for `signatures` transaction details
+    #[serde(default)] // Absent when the block is requested without rewards
     pub rewards: Rewards,
     pub block_time: Option<UnixTimestamp>,
     pub block_height: Option<u64>,
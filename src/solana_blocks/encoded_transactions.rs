@@ -1,5 +1,6 @@
 use crate::solana_blocks::{
-    Rewards, StringAmount, StringDecimals, TransactionError, UiInnerInstructions, UiMessage,
+    Rewards, StringAmount, StringDecimals, TransactionError, UiInnerInstructions,
+    UiLoadedAddresses, UiMessage,
 };
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +9,31 @@ use serde::{Deserialize, Serialize};
 pub struct EncodedTransactionWithStatusMeta {
     pub transaction: EncodedTransaction,
     pub meta: Option<UiTransactionStatusMeta>,
+    pub version: Option<TransactionVersion>, // Only present when `maxSupportedTransactionVersion` is set in the request
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", untagged)]
+pub enum TransactionVersion {
+    Legacy(Legacy),
+    Number(u8),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Legacy {
+    #[default]
+    Legacy,
+}
+
+impl TransactionVersion {
+    pub const LEGACY: Self = Self::Legacy(Legacy::Legacy);
+}
+
+impl Default for TransactionVersion {
+    fn default() -> Self {
+        Self::LEGACY
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -16,6 +42,7 @@ pub enum EncodedTransaction {
     LegacyBinary(String), // Old way of expressing base-58, retained for RPC backwards compatibility
     Binary(String, UiTransactionEncoding),
     Json(UiTransaction),
+    Accounts(UiAccountsList), // Returned instead of the transaction for `accounts` transaction details
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
@@ -34,6 +61,21 @@ pub struct ParsedAccount {
     pub pubkey: String,
     pub writable: bool,
     pub signer: bool,
+    pub source: Option<ParsedAccountSource>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParsedAccountSource {
+    Transaction,
+    LookupTable,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAccountsList {
+    pub signatures: Vec<String>,
+    pub account_keys: Vec<ParsedAccount>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -49,6 +91,7 @@ pub struct UiTransactionStatusMeta {
     pub pre_token_balances: Option<Vec<UiTransactionTokenBalance>>,
     pub post_token_balances: Option<Vec<UiTransactionTokenBalance>>,
     pub rewards: Option<Rewards>,
+    pub loaded_addresses: Option<UiLoadedAddresses>, // Accounts loaded from address lookup tables by versioned transactions
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -57,6 +100,8 @@ pub struct UiTransactionTokenBalance {
     pub account_index: u8,
     pub mint: String,
     pub ui_token_amount: UiTokenAmount,
+    pub owner: Option<String>,      // Absent on nodes older than v1.9
+    pub program_id: Option<String>, // Absent on nodes older than v1.10
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
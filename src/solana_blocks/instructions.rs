@@ -6,6 +6,7 @@ pub struct ParsedInstruction {
     pub program: String,
     pub program_id: String,
     pub parsed: serde_json::value::Value,
+    pub stack_height: Option<u32>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -14,6 +15,7 @@ pub struct UiPartiallyDecodedInstruction {
     pub program_id: String,
     pub accounts: Vec<String>,
     pub data: String,
+    pub stack_height: Option<u32>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -36,6 +38,7 @@ pub struct UiCompiledInstruction {
     pub program_id_index: u8,
     pub accounts: Vec<u8>,
     pub data: String,
+    pub stack_height: Option<u32>, // Only present for inner instructions on newer nodes
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
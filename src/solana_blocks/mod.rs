@@ -3,10 +3,15 @@ pub(crate) use global::*;
 mod errors;
 pub use errors::*;
 mod confirmed_blocks;
-pub(crate) use confirmed_blocks::*;
+pub use confirmed_blocks::*;
 mod encoded_transactions;
 pub(crate) use encoded_transactions::*;
+pub use encoded_transactions::{
+    EncodedTransactionWithStatusMeta, Legacy, TransactionVersion, UiTransactionEncoding,
+};
 mod message;
 pub(crate) use message::*;
 mod instructions;
 pub(crate) use instructions::*;
+mod wire_transaction;
+pub(crate) use wire_transaction::*;
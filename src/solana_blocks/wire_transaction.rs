@@ -0,0 +1,256 @@
+use crate::solana_blocks::{
+    MessageHeader, UiAddressTableLookup, UiCompiledInstruction, UiMessage, UiRawMessage,
+    UiTransaction,
+};
+use crate::{SolProbeError, SolProbeResult};
+use base64::Engine;
+
+/// Size in bytes of an ed25519 signature on the wire
+pub(crate) const SIGNATURE_BYTES: usize = 64;
+/// Size in bytes of a public key or a blockhash on the wire
+pub(crate) const PUBKEY_BYTES: usize = 32;
+/// The high bit of the first message byte is set for versioned messages
+pub(crate) const MESSAGE_VERSION_PREFIX: u8 = 0x80;
+
+/// Decodes the bincode wire format of a transaction returned by the RPC when
+/// a block is requested with `encoding: "base58"` or `encoding: "base64"`
+/// into the same shape returned for `encoding: "json"`.
+pub(crate) struct WireTransaction<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> WireTransaction<'a> {
+    pub(crate) fn decode_base58(blob: &str) -> SolProbeResult<UiTransaction> {
+        let bytes = bs58::decode(blob)
+            .into_vec()
+            .map_err(|_| SolProbeError::InvalidBase58)?;
+
+        WireTransaction::new(&bytes).decode()
+    }
+
+    pub(crate) fn decode_base64(blob: &str) -> SolProbeResult<UiTransaction> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(blob)
+            .map_err(|_| SolProbeError::InvalidBase64)?;
+
+        WireTransaction::new(&bytes).decode()
+    }
+
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub(crate) fn decode(&mut self) -> SolProbeResult<UiTransaction> {
+        let signatures = self.signatures()?;
+        let message = self.message()?;
+
+        if self.position != self.bytes.len() {
+            return Err(SolProbeError::MalformedTransaction);
+        }
+
+        Ok(UiTransaction {
+            signatures,
+            message: UiMessage::Raw(message),
+        })
+    }
+
+    fn signatures(&mut self) -> SolProbeResult<Vec<String>> {
+        let count = self.compact_u16()?;
+        let mut signatures = Vec::with_capacity(self.capacity(count, SIGNATURE_BYTES));
+
+        for _ in 0..count {
+            signatures.push(bs58::encode(self.take(SIGNATURE_BYTES)?).into_string());
+        }
+
+        Ok(signatures)
+    }
+
+    fn message(&mut self) -> SolProbeResult<UiRawMessage> {
+        let versioned = self.peek()? & MESSAGE_VERSION_PREFIX != 0;
+        if versioned && self.byte()? & !MESSAGE_VERSION_PREFIX != 0 {
+            return Err(SolProbeError::UnsupportedTransactionVersion);
+        }
+
+        let header = MessageHeader {
+            num_required_signatures: self.byte()?,
+            num_readonly_signed_accounts: self.byte()?,
+            num_readonly_unsigned_accounts: self.byte()?,
+        };
+
+        let key_count = self.compact_u16()?;
+        let mut account_keys = Vec::with_capacity(self.capacity(key_count, PUBKEY_BYTES));
+        for _ in 0..key_count {
+            account_keys.push(self.base58(PUBKEY_BYTES)?);
+        }
+
+        let recent_blockhash = self.base58(PUBKEY_BYTES)?;
+
+        let instruction_count = self.compact_u16()?;
+        // A program id index and two empty lengths
+        let mut instructions = Vec::with_capacity(self.capacity(instruction_count, 3));
+        for _ in 0..instruction_count {
+            instructions.push(self.instruction()?);
+        }
+
+        let address_table_lookups = if versioned {
+            let lookup_count = self.compact_u16()?;
+            // A table address and two empty lengths
+            let mut lookups = Vec::with_capacity(self.capacity(lookup_count, PUBKEY_BYTES + 2));
+            for _ in 0..lookup_count {
+                lookups.push(UiAddressTableLookup {
+                    account_key: self.base58(PUBKEY_BYTES)?,
+                    writable_indexes: self.byte_vec()?,
+                    readonly_indexes: self.byte_vec()?,
+                });
+            }
+
+            Some(lookups)
+        } else {
+            None
+        };
+
+        Ok(UiRawMessage {
+            header,
+            account_keys,
+            recent_blockhash,
+            instructions,
+            address_table_lookups,
+        })
+    }
+
+    fn instruction(&mut self) -> SolProbeResult<UiCompiledInstruction> {
+        let program_id_index = self.byte()?;
+        let accounts = self.byte_vec()?;
+        let data = bs58::encode(self.byte_vec()?).into_string();
+
+        Ok(UiCompiledInstruction {
+            program_id_index,
+            accounts,
+            data,
+            stack_height: None,
+        })
+    }
+
+    fn byte_vec(&mut self) -> SolProbeResult<Vec<u8>> {
+        let len = self.compact_u16()?;
+
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn base58(&mut self, len: usize) -> SolProbeResult<String> {
+        Ok(bs58::encode(self.take(len)?).into_string())
+    }
+
+    /// Reads a `short_vec` length, a little-endian u16 encoded in 1 to 3 bytes
+    /// with 7 bits per byte and the high bit marking continuation. Like the Solana
+    /// decoder it rejects overlong encodings, whose last byte is zero.
+    fn compact_u16(&mut self) -> SolProbeResult<usize> {
+        let mut value = 0usize;
+
+        for shift in 0..3 {
+            let byte = self.byte()?;
+            if byte == 0 && shift > 0 {
+                return Err(SolProbeError::MalformedTransaction);
+            }
+            value |= ((byte & 0x7f) as usize) << (shift * 7);
+
+            if byte & 0x80 == 0 {
+                return if value > u16::MAX as usize {
+                    Err(SolProbeError::MalformedTransaction)
+                } else {
+                    Ok(value)
+                };
+            }
+        }
+
+        Err(SolProbeError::MalformedTransaction)
+    }
+
+    /// `count` bounded by the number of elements of at least `element_size` bytes left, so
+    /// that a length read from the transaction cannot reserve more than the bytes can hold
+    fn capacity(&self, count: usize, element_size: usize) -> usize {
+        count.min((self.bytes.len() - self.position) / element_size)
+    }
+
+    fn peek(&self) -> SolProbeResult<u8> {
+        self.bytes
+            .get(self.position)
+            .copied()
+            .ok_or(SolProbeError::MalformedTransaction)
+    }
+
+    fn byte(&mut self) -> SolProbeResult<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+
+        Ok(byte)
+    }
+
+    fn take(&mut self, len: usize) -> SolProbeResult<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .ok_or(SolProbeError::MalformedTransaction)?;
+        let slice = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(SolProbeError::MalformedTransaction)?;
+        self.position = end;
+
+        Ok(slice)
+    }
+}
+
+#[test]
+fn decode_legacy_wire_transaction() {
+    let mut bytes = vec![1u8];
+    bytes.extend_from_slice(&[7u8; SIGNATURE_BYTES]);
+    bytes.extend_from_slice(&[1, 0, 1]);
+    bytes.push(2);
+    bytes.extend_from_slice(&[1u8; PUBKEY_BYTES]);
+    bytes.extend_from_slice(&[0u8; PUBKEY_BYTES]);
+    bytes.extend_from_slice(&[9u8; PUBKEY_BYTES]);
+    bytes.extend_from_slice(&[1, 1, 1, 0, 4, 2, 0, 0, 0]);
+
+    let blob = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    let transaction = WireTransaction::decode_base64(&blob).unwrap();
+    let message = match transaction.message {
+        UiMessage::Raw(message) => message,
+        UiMessage::Parsed(_) => unreachable!(),
+    };
+
+    assert_eq!(
+        transaction.signatures,
+        vec![bs58::encode([7u8; 64]).into_string()]
+    );
+    assert_eq!(message.header.num_readonly_unsigned_accounts, 1);
+    assert_eq!(message.account_keys[1], "11111111111111111111111111111111");
+    assert_eq!(message.instructions[0].accounts, vec![0]);
+    assert_eq!(
+        message.instructions[0].data,
+        bs58::encode([2, 0, 0, 0]).into_string()
+    );
+    assert_eq!(
+        WireTransaction::new(&bytes[..bytes.len() - 1]).decode(),
+        Err(SolProbeError::MalformedTransaction)
+    );
+    // One signature written as the overlong `[0x81, 0x00]`
+    let mut overlong = vec![0x81, 0x00];
+    overlong.extend_from_slice(&bytes[1..]);
+    assert_eq!(
+        WireTransaction::new(&overlong).decode(),
+        Err(SolProbeError::MalformedTransaction)
+    );
+    let mut huge = WireTransaction::new(&[0xff, 0xff, 0x03]);
+    assert_eq!(huge.compact_u16(), Ok(0xffff));
+    assert_eq!(huge.capacity(0xffff, SIGNATURE_BYTES), 0);
+    assert_eq!(
+        WireTransaction::new(&bytes).capacity(0xffff, PUBKEY_BYTES),
+        bytes.len() / PUBKEY_BYTES
+    );
+    assert_eq!(
+        WireTransaction::new(&[0x80, 0x80, 0x04]).compact_u16(),
+        Err(SolProbeError::MalformedTransaction)
+    );
+}
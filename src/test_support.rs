@@ -0,0 +1,341 @@
+//! Offline testing against recorded node responses. `FixtureRecorder` captures the
+//! responses of a real node to a directory of fixtures, `MockRpcServer` serves them back.
+
+use crate::{RpcError, SolProbeError, SolProbeResult, Transport, TransportFuture};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::{fs, io};
+
+/// A recorded request and the response of the node, stored as one JSON file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixture {
+    pub method: String,
+    pub params: serde_json::Value,
+    /// The whole JSON-RPC response, its `id` is replaced by the one of the request served
+    pub response: serde_json::Value,
+}
+
+impl Fixture {
+    /// The name `FixtureRecorder` gives the file of the fixture, unique per method and params
+    /// as `MockRpcServer` matches them
+    pub fn file_name(&self) -> String {
+        let (_, params) = self.key();
+        let digest = Sha256::digest(params.as_bytes());
+        let hash = digest[..8]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+
+        format!("{}-{}.json", self.method, hash)
+    }
+
+    fn key(&self) -> (String, String) {
+        fixture_key(&self.method, &self.params)
+    }
+}
+
+/// `params` are compared as JSON values, so the order of object keys does not matter
+fn fixture_key(method: &str, params: &serde_json::Value) -> (String, String) {
+    let params = match params {
+        serde_json::Value::Null => serde_json::Value::Array(Vec::default()),
+        params => params.clone(),
+    };
+
+    (method.to_string(), params.to_string())
+}
+
+/// An in-process JSON-RPC server answering from fixtures, keyed by method and params. It is a
+/// `Transport`, hand it to `RpcClient::new`. Requests without a fixture fail with
+/// `SolProbeError::Transport` naming the missing method and params.
+#[derive(Debug, Default)]
+pub struct MockRpcServer {
+    fixtures: HashMap<(String, String), serde_json::Value>,
+    requests: Mutex<Vec<serde_json::Value>>,
+    /// Failures that take the place of the fixtures for the next posts, in order
+    scripted: Mutex<VecDeque<Scripted>>,
+}
+
+#[derive(Debug)]
+enum Scripted {
+    Fail(SolProbeError),
+    Error(RpcError),
+}
+
+impl MockRpcServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A server answering from every `.json` fixture in `dir`
+    pub fn load<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let mut server = Self::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+
+            let fixture: Fixture = serde_json::from_slice(&fs::read(&path)?)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            server.insert(fixture);
+        }
+
+        Ok(server)
+    }
+
+    pub fn insert(&mut self, fixture: Fixture) -> &mut Self {
+        self.fixtures.insert(fixture.key(), fixture.response);
+
+        self
+    }
+
+    /// Answers `method` called with `params` with `result`
+    pub fn result(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+        result: serde_json::Value,
+    ) -> &mut Self {
+        self.insert(Fixture {
+            method: method.to_string(),
+            params,
+            response: serde_json::json!({ "jsonrpc": "2.0", "result": result, "id": 0 }),
+        })
+    }
+
+    /// Fails the next post with `error` instead of answering it, like a 429 of a throttled
+    /// node. Failures and errors are used up in the order they were queued.
+    pub fn fail(&self, error: SolProbeError) -> &Self {
+        self.script().push_back(Scripted::Fail(error));
+
+        self
+    }
+
+    /// Answers every request of the next post with the JSON-RPC `error` instead of its fixture
+    pub fn error(&self, error: RpcError) -> &Self {
+        self.script().push_back(Scripted::Error(error));
+
+        self
+    }
+
+    /// The requests served so far, in order, including the ones that failed
+    pub fn requests(&self) -> Vec<serde_json::Value> {
+        self.lock().clone()
+    }
+
+    fn respond(
+        &self,
+        request: &serde_json::Value,
+        scripted: Option<&Scripted>,
+    ) -> SolProbeResult<serde_json::Value> {
+        self.lock().push(request.clone());
+
+        match scripted {
+            Some(Scripted::Fail(error)) => return Err(error.clone()),
+            Some(Scripted::Error(error)) => {
+                return Ok(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "error": error,
+                    "id": request["id"],
+                }))
+            }
+            None => {}
+        }
+
+        let method = request["method"].as_str().unwrap_or_default();
+        let mut response = self
+            .fixtures
+            .get(&fixture_key(method, &request["params"]))
+            .cloned()
+            .ok_or_else(|| {
+                SolProbeError::Transport(format!("no fixture for {} {}", method, request["params"]))
+            })?;
+        response["id"] = request["id"].clone();
+
+        Ok(response)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<serde_json::Value>> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    fn script(&self) -> MutexGuard<'_, VecDeque<Scripted>> {
+        self.scripted
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl Transport for MockRpcServer {
+    fn post<'a>(&'a self, _url: &'a str, body: String) -> TransportFuture<'a> {
+        let scripted = self.script().pop_front();
+        let response = serde_json::from_str::<serde_json::Value>(&body)
+            .map_err(|_| SolProbeError::InvalidRequest)
+            .and_then(|request| match request {
+                // Every request of a batch is recorded before a failure fails it as a whole
+                serde_json::Value::Array(batch) => batch
+                    .iter()
+                    .map(|request| self.respond(request, scripted.as_ref()))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .collect::<SolProbeResult<Vec<_>>>()
+                    .map(serde_json::Value::Array),
+                request => self.respond(&request, scripted.as_ref()),
+            })
+            .map(|response| response.to_string());
+
+        Box::pin(async { response })
+    }
+}
+
+/// Wraps the transport to a real node and writes every request it answers to `dir` as a
+/// fixture for `MockRpcServer`, overwriting older recordings of the same request
+pub struct FixtureRecorder<T: Transport> {
+    transport: T,
+    dir: PathBuf,
+}
+
+impl<T: Transport> FixtureRecorder<T> {
+    pub fn new<P: Into<PathBuf>>(transport: T, dir: P) -> Self {
+        Self {
+            transport,
+            dir: dir.into(),
+        }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn record(&self, request: &str, response: &str) -> SolProbeResult<()> {
+        let parse = |json: &str| {
+            serde_json::from_str::<serde_json::Value>(json)
+                .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))
+        };
+        let pairs = match (parse(request)?, parse(response)?) {
+            (serde_json::Value::Array(requests), serde_json::Value::Array(responses)) => requests
+                .into_iter()
+                .filter_map(|request| {
+                    responses
+                        .iter()
+                        .find(|response| response["id"] == request["id"])
+                        .map(|response| (request, response.clone()))
+                })
+                .collect(),
+            (request, response) => vec![(request, response)],
+        };
+
+        let io_error = |error: io::Error| SolProbeError::FixtureIo(error.to_string());
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        for (request, response) in pairs {
+            let fixture = Fixture {
+                method: request["method"].as_str().unwrap_or_default().to_string(),
+                params: request["params"].clone(),
+                response,
+            };
+            let json = serde_json::to_string_pretty(&fixture)
+                .map_err(|error| SolProbeError::InvalidResponse(error.to_string()))?;
+
+            fs::write(self.dir.join(fixture.file_name()), json + "\n").map_err(io_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Transport> Transport for FixtureRecorder<T> {
+    fn post<'a>(&'a self, url: &'a str, body: String) -> TransportFuture<'a> {
+        self.post_request(url, body, false)
+    }
+
+    fn post_request<'a>(
+        &'a self,
+        url: &'a str,
+        body: String,
+        idempotent: bool,
+    ) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self
+                .transport
+                .post_request(url, body.clone(), idempotent)
+                .await?;
+            self.record(&body, &response)?;
+
+            Ok(response)
+        })
+    }
+}
+
+#[test]
+fn recorded_fixtures_replay_offline() {
+    use crate::{RpcClient, RpcRequest};
+
+    let dir = std::env::temp_dir().join(format!("sol-probe-fixtures-{}", std::process::id()));
+
+    let mut node = MockRpcServer::new();
+    node.result(
+        "getBlockTime",
+        serde_json::json!([7]),
+        serde_json::json!(1700000000),
+    )
+    .result("getSlot", serde_json::json!([]), serde_json::json!(8));
+    let recorder = RpcClient::new("", FixtureRecorder::new(node, &dir));
+
+    smol::block_on(async {
+        assert_eq!(recorder.get_slot().await, Ok(8));
+
+        let mut batch = recorder.batch();
+        let block_time = batch.add::<_, i64>(RpcRequest::GetBlockTime, (7,)).unwrap();
+        let mut responses = batch.send().await.unwrap();
+        assert_eq!(responses.take(&block_time), Ok(1_700_000_000));
+    });
+
+    let replay = RpcClient::new("", MockRpcServer::load(&dir).unwrap());
+    smol::block_on(async {
+        assert_eq!(replay.get_slot().await, Ok(8));
+        assert_eq!(
+            replay.send::<_, u64>(RpcRequest::GetBlockHeight, ()).await,
+            Err(SolProbeError::Transport(
+                "no fixture for getBlockHeight []".into()
+            ))
+        );
+    });
+    assert_eq!(replay.transport().requests().len(), 2);
+
+    // Requests without params are saved as the fixture with empty params
+    let fixture = |params| Fixture {
+        method: "getSlot".to_string(),
+        params,
+        response: serde_json::json!({ "jsonrpc": "2.0", "result": 8, "id": 1 }),
+    };
+    assert_eq!(
+        fixture(serde_json::Value::Null).file_name(),
+        fixture(serde_json::json!([])).file_name()
+    );
+    assert!(dir
+        .join(fixture(serde_json::json!([])).file_name())
+        .exists());
+
+    // A directory that cannot be created is not mistaken for a failing node
+    let mut node = MockRpcServer::new();
+    node.result("getSlot", serde_json::json!([]), serde_json::json!(8));
+    let blocked = RpcClient::new("", FixtureRecorder::new(node, dir.join("getSlot")));
+    fs::write(dir.join("getSlot"), "").unwrap();
+    assert!(matches!(
+        smol::block_on(blocked.get_slot()),
+        Err(SolProbeError::FixtureIo(_))
+    ));
+
+    fs::remove_dir_all(&dir).unwrap();
+}